@@ -1,15 +1,34 @@
+import { getEventListeners } from 'node:events'
 import test from 'ava'
 import type { TrainingConfig } from '../index.js'
 import {
+  CardState,
   DEFAULT_PARAMETERS,
+  Dataset,
   FSRS,
+  FSRS6_DEFAULT_DECAY,
   FSRSItem,
   FSRSReview,
+  MemoryState,
+  NextStates,
+  ReviewOrder,
+  Scheduler,
   checkAndFillParameters,
+  compareEasyDays,
+  computeOptimalRetention,
   defaultSimulatorConfig,
   evaluateWithTimeSeriesSplits,
+  evaluateWithTimeSeriesSplitsAsync,
   filterOutlier,
+  fuzzInterval,
+  fuzzRange,
+  loadBalanceInterval,
+  parameterInfo,
+  parametersFromArray,
+  parametersToArray,
+  rescheduleCards,
   simulate,
+  simulateAsync,
 } from '../index.js'
 
 test('schedules next states with default parameters', (t) => {
@@ -123,3 +142,598 @@ test('filters outliers and exposes time-series evaluation errors', (t) => {
 
   t.throws(() => evaluateWithTimeSeriesSplits([]))
 })
+
+test('evaluates on a background thread', async (t) => {
+  const fsrs = new FSRS()
+  const items = [
+    new FSRSItem([new FSRSReview(3, 0), new FSRSReview(3, 1)]),
+    new FSRSItem([new FSRSReview(1, 0), new FSRSReview(3, 1), new FSRSReview(3, 3)]),
+  ]
+
+  const result = await fsrs.evaluateAsync(items, { progress: () => {} })
+  t.is(result.logLoss, fsrs.evaluate(items).logLoss)
+
+  await t.throwsAsync(fsrs.evaluateAsync([]), { message: /NotEnoughData/ })
+  await t.throwsAsync(evaluateWithTimeSeriesSplitsAsync([]), { message: /NotEnoughData/ })
+})
+
+test('aborted signal rejects computeParameters', async (t) => {
+  const fsrs = new FSRS()
+  const controller = new AbortController()
+  controller.abort()
+
+  await t.throwsAsync(
+    fsrs.computeParameters([new FSRSItem([new FSRSReview(3, 0), new FSRSReview(3, 1)])], {
+      signal: controller.signal,
+    }),
+    { code: 'Cancelled', message: 'AbortError' },
+  )
+})
+
+function trainingItems(cards: number) {
+  const items = []
+  for (let card = 0; card < cards; card++) {
+    const reviews = [new FSRSReview((card % 4) + 1, 0)]
+    let interval = 1
+    for (let review = 1; review < 3 + (card % 6); review++) {
+      const rating = (card * 7 + review) % 9 === 0 ? 1 : 3
+      reviews.push(new FSRSReview(rating, interval))
+      interval = rating === 1 ? 1 : interval * 2 + (card % 3)
+      items.push(new FSRSItem([...reviews]))
+    }
+  }
+  return items
+}
+
+test('aborting during training rejects computeParameters', async (t) => {
+  const controller = new AbortController()
+  const training = new FSRS().computeParameters(trainingItems(3000), {
+    signal: controller.signal,
+    timeout: 10,
+    progress: () => controller.abort(),
+  })
+
+  await t.throwsAsync(training, { code: 'Cancelled', message: 'AbortError' })
+})
+
+test('computeParameters removes its abort listener once settled', async (t) => {
+  const controller = new AbortController()
+  await new FSRS().computeParameters(trainingItems(100), { signal: controller.signal })
+
+  t.is(getEventListeners(controller.signal, 'abort').length, 0)
+})
+
+test('training progress reports each split and epoch', async (t) => {
+  const events: Array<{
+    splitIndex?: number
+    epoch?: number
+    epochTotal?: number
+    itemsProcessed?: number
+    itemsTotal?: number
+  }> = []
+  await new FSRS().computeParameters(trainingItems(1000), {
+    timeout: 10,
+    progress: (_, value) => events.push(value),
+  })
+
+  t.true(events.length > 0)
+  for (const event of events) {
+    t.is(event.splitIndex, 0)
+    t.true(event.epoch! <= event.epochTotal!)
+    t.true(event.itemsProcessed! <= event.itemsTotal!)
+  }
+  const last = events[events.length - 1]
+  t.is(last.epoch, last.epochTotal)
+  t.is(last.itemsProcessed, last.itemsTotal)
+})
+
+test('imports FSRSItems from a revlog CSV', (t) => {
+  const csv = [
+    'card_id,review_time,review_rating,review_state',
+    // reviewed before a reset: dropped
+    '1,1700000000000,3,2',
+    '1,1700100000000,1,0',
+    '1,1700200000000,3,1',
+    '1,1700400000000,3,2',
+    '2,1700010000000,4,0',
+    '2,1700020000000,3,1',
+    '2,1700300000000,3,2',
+  ].join('\n')
+
+  const { items, cardIds } = FSRSItem.fromRevlogCsv(Buffer.from(csv))
+
+  t.deepEqual(cardIds, [1, 2, 1])
+  t.deepEqual(
+    items.map((item) => item.reviews.map((review) => [review.rating, review.deltaT])),
+    [
+      [
+        [1, 0],
+        [3, 2],
+      ],
+      [
+        [4, 0],
+        [3, 0],
+        [3, 4],
+      ],
+      [
+        [1, 0],
+        [3, 2],
+        [3, 2],
+      ],
+    ],
+  )
+})
+
+test('builds FSRSItems from timestamped review events', (t) => {
+  const hour = 60 * 60 * 1000
+  const start = Date.UTC(2024, 0, 1, 12)
+
+  const { items, cardIds } = FSRSItem.fromReviewEvents(
+    [
+      { cardId: 2, timestampMs: start + hour, rating: 3 },
+      { cardId: 1, timestampMs: start, rating: 1 },
+      { cardId: 1, timestampMs: start + 2 * hour, rating: 3 },
+      // 03:00 the next morning still counts as the first day
+      { cardId: 1, timestampMs: start + 15 * hour, rating: 3 },
+      { cardId: 1, timestampMs: start + 39 * hour, rating: 3 },
+      { cardId: 2, timestampMs: start + 72 * hour, rating: 2 },
+    ],
+    { nextDayStartsAt: 4 },
+  )
+
+  t.deepEqual(cardIds, [1, 2])
+  t.deepEqual(
+    items.map((item) => item.reviews.map((review) => review.deltaT)),
+    [
+      [0, 0, 0, 1],
+      [0, 3],
+    ],
+  )
+  t.true(items.every((item) => item.longTermReviewCnt() === 1))
+  t.throws(() => FSRSItem.fromReviewEvents([{ cardId: 1, timestampMs: start, rating: 0 }]), {
+    message: /rating/,
+  })
+})
+
+test('computes parameters from typed arrays', async (t) => {
+  const fsrs = new FSRS()
+  const items = [
+    new FSRSItem([new FSRSReview(3, 0), new FSRSReview(3, 1)]),
+    new FSRSItem([new FSRSReview(1, 0), new FSRSReview(3, 1), new FSRSReview(3, 3)]),
+  ]
+
+  const parameters = await fsrs.computeParametersFromArrays(
+    new Uint32Array([3, 3, 1, 3, 3]),
+    new Uint32Array([0, 1, 0, 1, 3]),
+    new Uint32Array([0, 2, 5]),
+  )
+  t.deepEqual(parameters, await fsrs.computeParameters(items))
+
+  t.throws(
+    () => fsrs.computeParametersFromArrays(new Uint32Array([3]), new Uint32Array([0]), new Uint32Array([0, 2])),
+    { message: /itemOffsets/ },
+  )
+  const ratings = new Uint32Array([3, 3, 3, 3, 3])
+  const deltaTs = new Uint32Array([0, 1, 0, 1, 2])
+  for (const itemOffsets of [new Uint32Array([0, 10, 5]), new Uint32Array([0, 2, 2, 5])]) {
+    t.throws(() => fsrs.computeParametersFromArrays(ratings, deltaTs, itemOffsets), { message: /itemOffsets/ })
+    t.throws(() => Dataset.fromArrays(ratings, deltaTs, itemOffsets), { message: /itemOffsets/ })
+  }
+})
+
+test('reuses a native Dataset across APIs', async (t) => {
+  const fsrs = new FSRS()
+  const items = [
+    new FSRSItem([new FSRSReview(3, 0), new FSRSReview(3, 1)]),
+    new FSRSItem([new FSRSReview(1, 0), new FSRSReview(3, 1), new FSRSReview(3, 3)]),
+    new FSRSItem([new FSRSReview(3, 0), new FSRSReview(4, 2)]),
+  ]
+  const dataset = new Dataset(items, [1, 2, 3])
+
+  const [head, tail] = dataset.splitAt(1)
+  t.is(head.length, 1)
+  t.deepEqual(tail.cardIds, [2, 3])
+  t.deepEqual(dataset.select(new Uint32Array([2, 0])).cardIds, [3, 1])
+  t.deepEqual(dataset.filter((item) => item.reviews.length === 2).cardIds, [1, 3])
+  t.throws(() => dataset.push([items[0]]), { message: /cardIds/ })
+
+  t.deepEqual(await fsrs.computeParameters(dataset), await fsrs.computeParameters(items, { cardIds: [1, 2, 3] }))
+  t.is(fsrs.evaluate(dataset).logLoss, fsrs.evaluate(items).logLoss)
+  t.is((await fsrs.evaluateAsync(dataset)).logLoss, fsrs.evaluate(dataset).logLoss)
+})
+
+test('filterOutlier keeps the card ids of a Dataset', (t) => {
+  const items = Array.from(
+    { length: 33 },
+    (_, i) => new FSRSItem([new FSRSReview(3, 0), new FSRSReview(3, i % 11 === 0 ? 50 : 1)]),
+  )
+  const cardIds = items.map((_, i) => 100 + i)
+  const keptCardIds = cardIds.filter((_, i) => i % 11 !== 0)
+
+  const result = filterOutlier(new Dataset(items, cardIds), new Dataset(items, cardIds))
+  t.true(result.trainset instanceof Dataset)
+  t.deepEqual((result.trainset as Dataset).cardIds, keptCardIds)
+  t.deepEqual((result.datasetForInitialization as Dataset).cardIds, keptCardIds)
+  t.true(Array.isArray(filterOutlier(items, items).trainset))
+})
+
+test('round-trips binding types through JSON', (t) => {
+  const item = new FSRSItem([new FSRSReview(3, 0), new FSRSReview(1, 2)])
+  t.is(JSON.stringify(item), '{"reviews":[{"rating":3,"deltaT":0},{"rating":1,"deltaT":2}]}')
+  t.deepEqual(FSRSItem.fromJSON(JSON.parse(JSON.stringify(item))).toJSON(), item.toJSON())
+
+  const nextStates = new FSRS().nextStates(new MemoryState(3.3, 5.1), 0.9, 3)
+  const restored = NextStates.fromJSON(JSON.parse(JSON.stringify(nextStates)))
+  t.deepEqual(restored.toJSON(), nextStates.toJSON())
+  t.is(restored.good.memory.stability, nextStates.good.memory.stability)
+  t.is(MemoryState.fromJSON(new MemoryState(1.1, 2).toJSON()).stability, new MemoryState(1.1, 2).stability)
+})
+
+test('computes current retrievability and samples the forgetting curve', (t) => {
+  const fsrs = new FSRS()
+  const memoryState = new MemoryState(10, 5)
+
+  t.true(Math.abs(fsrs.currentRetrievability(memoryState, 10) - 0.9) < 1e-6)
+  const curve = fsrs.forgettingCurve(memoryState, [0, 1, 10, 100])
+  t.is(curve[0], 1)
+  t.is(curve[2], fsrs.currentRetrievability(memoryState, 10))
+  t.true(curve.every((r, i) => i === 0 || r < curve[i - 1]))
+  t.throws(() => fsrs.currentRetrievability(memoryState, -1), { message: /daysElapsed/ })
+})
+
+test('computes the optimal retention with its cost curve', async (t) => {
+  const config = { ...defaultSimulatorConfig(), deckSize: 20, learnSpan: 400 }
+  const progress: number[] = []
+  const result = await computeOptimalRetention(DEFAULT_PARAMETERS, config, {
+    progress: (_, value) => progress.push(value.current),
+  })
+
+  const costs = result.costCurve.map((point) => point.cost)
+  t.true(result.desiredRetention >= 0.7 && result.desiredRetention <= 0.95)
+  t.is(result.costCurve.find((point) => point.desiredRetention === result.desiredRetention)?.cost, Math.min(...costs))
+  t.true(progress.every((current) => current < result.costCurve.length))
+
+  const controller = new AbortController()
+  controller.abort()
+  await t.throwsAsync(computeOptimalRetention(DEFAULT_PARAMETERS, config, { signal: controller.signal }), {
+    message: 'AbortError',
+  })
+})
+
+test('calls JS scheduling hooks from the simulator', async (t) => {
+  const config = { ...defaultSimulatorConfig(), deckSize: 50, learnSpan: 60 }
+  const seen = { postScheduling: 0, reviewPriority: 0 }
+  const result = await simulateAsync(
+    DEFAULT_PARAMETERS,
+    0.9,
+    {
+      ...config,
+      postSchedulingFn: (context) => {
+        seen.postScheduling++
+        return context.card.interval * 2
+      },
+      reviewPriorityFn: (card) => {
+        seen.reviewPriority++
+        return -card.id
+      },
+    },
+    1,
+  )
+
+  t.true(seen.postScheduling > 0)
+  t.true(seen.reviewPriority > 0)
+  t.notDeepEqual(result.reviewCntPerDay, simulate(DEFAULT_PARAMETERS, 0.9, config, 1).reviewCntPerDay)
+  await t.throwsAsync(
+    simulateAsync(DEFAULT_PARAMETERS, 0.9, {
+      ...config,
+      postSchedulingFn: () => {
+        throw new Error('boom')
+      },
+    }),
+    { message: /postSchedulingFn threw: Error: boom/ },
+  )
+  t.throws(() => simulate(DEFAULT_PARAMETERS, 0.9, { ...config, reviewPriorityFn: () => 0 }), {
+    message: /simulateAsync/,
+  })
+})
+
+test('simulates starting from existing cards', (t) => {
+  const config = { ...defaultSimulatorConfig(), deckSize: 200, learnSpan: 30, learnLimit: 0 }
+  const existingCards = Array.from({ length: 100 }, (_, i) => ({
+    id: i + 1,
+    stability: 5 + i,
+    difficulty: 5,
+    lastDate: -10 - (i % 10),
+    due: (i % 20) - 5,
+  }))
+
+  const sum = (values: number[]) => values.reduce((a, b) => a + b, 0)
+  t.is(sum(simulate(DEFAULT_PARAMETERS, 0.9, config, 1).reviewCntPerDay), 0)
+  t.true(sum(simulate(DEFAULT_PARAMETERS, 0.9, config, 1, existingCards).reviewCntPerDay) > 0)
+  const card = { id: 1, stability: 3, difficulty: 5, lastDate: 0, due: 1 }
+  t.throws(() => simulate(DEFAULT_PARAMETERS, 0.9, config, 1, [{ ...card, due: -1 }]), { message: /lastDate/ })
+  t.throws(() => simulate(DEFAULT_PARAMETERS, 0.9, config, 1, [{ ...card, id: 0 }]), {
+    message: 'ExistingCard id must be 1 or more',
+  })
+})
+
+test('moves cards through learning, review and relearning', (t) => {
+  const scheduler = new Scheduler()
+  const minute = 60 * 1000
+  const day = 24 * 60 * minute
+
+  let { card, log } = scheduler.review(scheduler.newCard(0), 3, 0)
+  t.is(log.state, CardState.New)
+  t.like(card, { state: CardState.Learning, step: 1, due: 10 * minute, reps: 1 })
+
+  card = scheduler.review(card, 3, 10 * minute).card
+  t.is(card.state, CardState.Review)
+  t.is(card.due, 10 * minute + card.scheduledDays * day)
+
+  const lapse = scheduler.review(card, 1, card.due)
+  t.like(lapse.card, { state: CardState.Relearning, step: 0, due: card.due + 10 * minute, lapses: 1 })
+  t.is(lapse.log.elapsedDays, card.scheduledDays)
+  t.is(scheduler.review(lapse.card, 3, lapse.card.due).card.state, CardState.Review)
+  t.throws(() => scheduler.review(card, 5, card.due), { message: /rating/ })
+})
+
+test('uses configurable learning steps for scheduling and simulation', (t) => {
+  const scheduler = new Scheduler(null, { learningSteps: [1, 5, 30], relearningSteps: [] })
+  const minute = 60 * 1000
+  t.deepEqual(scheduler.learningSteps, [1, 5, 30])
+
+  let { card } = scheduler.review(scheduler.newCard(0), 3, 0)
+  t.like(card, { state: CardState.Learning, step: 1, due: 5 * minute })
+  card = scheduler.review(card, 3, card.due).card
+  t.like(card, { state: CardState.Learning, step: 2, due: 35 * minute })
+  card = scheduler.review(card, 3, card.due).card
+  t.is(card.state, CardState.Review)
+  t.like(scheduler.review(card, 1, card.due).card, { state: CardState.Review, lapses: 1 })
+
+  t.like(scheduler.simulatorConfig(), { learningStepCount: 3, relearningStepCount: 0, maxIvl: 36500 })
+  t.throws(() => new Scheduler(null, { learningSteps: [0] }), { message: /learningSteps/ })
+})
+
+test('fuzzes intervals deterministically by card id and review count', (t) => {
+  t.deepEqual(fuzzRange(2, 0), { min: 2, max: 2 })
+  t.deepEqual(fuzzRange(30, 0), { min: 27, max: 33 })
+  t.deepEqual(fuzzRange(30, 0, 31), { min: 27, max: 31 })
+
+  const intervals = Array.from({ length: 20 }, (_, cardId) => fuzzInterval(30, 0, { cardId, reviewCount: 5 }))
+  t.true(intervals.every((interval) => interval >= 27 && interval <= 33))
+  t.true(new Set(intervals).size > 1)
+  t.is(fuzzInterval(30, 0, { cardId: 3, reviewCount: 5 }), intervals[3])
+
+  const fsrs = new FSRS()
+  const memoryState = new MemoryState(20, 5)
+  const fuzz = { cardId: 1, reviewCount: 3 }
+  const { good } = fsrs.nextStates(memoryState, 0.9, 20, fuzz)
+  t.true(Number.isInteger(good.interval))
+  t.deepEqual(fsrs.nextStates(memoryState, 0.9, 20, fuzz).toJSON(), fsrs.nextStates(memoryState, 0.9, 20, fuzz).toJSON())
+  const { min, max } = fuzzRange(fsrs.nextStates(memoryState, 0.9, 20).good.interval, 20)
+  t.true(good.interval >= min && good.interval <= max)
+})
+
+test('moves intervals to the least busy day', (t) => {
+  const dueCounts = new Uint32Array(60).fill(10)
+  dueCounts[28] = 3
+  dueCounts[32] = 3
+  t.is(loadBalanceInterval(30, 0, dueCounts), 28)
+  t.is(loadBalanceInterval(30.6, 0, new Uint32Array()), 31)
+  t.is(loadBalanceInterval(30, 0, dueCounts, 30), 28)
+
+  const fsrs = new FSRS()
+  const memoryState = new MemoryState(20, 5)
+  const ideal = fsrs.nextStates(memoryState, 0.9, 20).good.interval
+  const { min, max } = fuzzRange(ideal, 20)
+  const busy = Uint32Array.from({ length: max + 1 }, (_, day) => (day === min ? 1 : 50))
+  t.is(fsrs.nextStates(memoryState, 0.9, 20, null, { dueCounts: busy }).good.interval, min)
+  t.throws(() => fsrs.nextStates(memoryState, 0.9, 20, { cardId: 1, reviewCount: 1 }, { dueCounts: busy }), {
+    message: /can't be combined/,
+  })
+})
+
+test('moves intervals away from easy days', (t) => {
+  const weekend = { multipliers: [1, 1, 1, 1, 1, 0, 0], weekday: 0 }
+  // 33 days after a Monday is a Saturday
+  t.is(loadBalanceInterval(33, 0, new Uint32Array(), null, weekend), 32)
+  t.is(loadBalanceInterval(30, 0, new Uint32Array(), null, weekend), 30)
+  t.throws(() => loadBalanceInterval(30, 0, new Uint32Array(), null, { multipliers: [1], weekday: 0 }), {
+    message: /multipliers/,
+  })
+
+  const config = { ...defaultSimulatorConfig(), deckSize: 200, learnSpan: 60, easyDays: weekend }
+  const { baseline, easyDays } = compareEasyDays(DEFAULT_PARAMETERS, 0.9, config, 1)
+  const weekendReviews = ({ reviewsPerWeekday }: { reviewsPerWeekday: number[] }) =>
+    reviewsPerWeekday[5] + reviewsPerWeekday[6]
+  t.true(weekendReviews(easyDays) < weekendReviews(baseline))
+  t.true(easyDays.trueRetention > 0 && easyDays.totalCost > 0)
+
+  const uniform = { ...config, easyDays: { multipliers: [1, 1, 1, 1, 1, 1, 1], weekday: 0 } }
+  const comparison = compareEasyDays(DEFAULT_PARAMETERS, 0.9, uniform, 1)
+  t.deepEqual(comparison.easyDays, comparison.baseline)
+})
+
+test('reschedules cards with new parameters', (t) => {
+  const reviews = [
+    { rating: 3, deltaT: 0 },
+    { rating: 3, deltaT: 3 },
+    { rating: 3, deltaT: 8 },
+  ]
+  const cards = [
+    { id: 1, reviews, lastReview: 100, due: 120 },
+    { id: 2, reviews: [{ rating: 1, deltaT: 0 }], lastReview: 90, due: 91 },
+  ]
+  const [card, overdue] = rescheduleCards(cards, DEFAULT_PARAMETERS, 0.9, 105)
+  const fsrs = new FSRS()
+  const memory = fsrs.memoryState(new FSRSItem(reviews.map(({ rating, deltaT }) => new FSRSReview(rating, deltaT))))
+  t.deepEqual(card.memory, memory.toJSON())
+  t.is(card.due, 100 + card.interval)
+  t.is(card.daysMoved, card.due - 120)
+  t.like(overdue, { id: 2, due: 105, daysMoved: 14 })
+
+  const longer = rescheduleCards(cards, DEFAULT_PARAMETERS, 0.8, 105, { enableFuzz: true })
+  t.true(longer[0].due > card.due)
+  t.deepEqual(rescheduleCards(cards, DEFAULT_PARAMETERS, 0.8, 105, { enableFuzz: true }), longer)
+  t.throws(() => rescheduleCards([{ id: 3, reviews: [], lastReview: 0, due: 0 }], DEFAULT_PARAMETERS, 0.9, 0), {
+    message: /no reviews/,
+  })
+  const ancient = { ...cards[0], lastReview: -(2 ** 40) }
+  t.throws(() => rescheduleCards([ancient], DEFAULT_PARAMETERS, 0.9, 105), { message: /out of range/ })
+  t.throws(() => rescheduleCards([ancient], DEFAULT_PARAMETERS, 0.9, 105, { enableFuzz: true }), {
+    message: /out of range/,
+  })
+})
+
+test('selects cards to postpone or advance', (t) => {
+  const fsrs = new FSRS()
+  const memory = (stability: number) => ({ stability, difficulty: 5 })
+  const cards = [
+    { id: 1, memory: memory(100), lastReview: 0, due: 90 },
+    { id: 2, memory: memory(5), lastReview: 85, due: 90 },
+    { id: 3, memory: memory(30), lastReview: 70, due: 95 },
+    { id: 4, memory: memory(20), lastReview: 80, due: 101 },
+    { id: 5, memory: memory(50), lastReview: 60, due: 140 },
+  ]
+
+  const postponed = fsrs.postponeCards(cards, 100)
+  t.deepEqual(postponed.map(({ id }) => id), [3, 1, 2])
+  t.true(postponed.every(({ due }) => due > 100))
+  t.deepEqual(fsrs.postponeCards(cards, 100, { count: 1 }), postponed.slice(0, 1))
+  const kept = fsrs.postponeCards(cards, 100, { minimumRetrievability: 0.85 })
+  t.true(kept.length < postponed.length && kept.every(({ retrievability }) => retrievability >= 0.85))
+
+  const advanced = fsrs.advanceCards(cards, 100)
+  t.deepEqual(
+    advanced.map(({ id, due }) => [id, due]),
+    [
+      [4, 100],
+      [5, 100],
+    ],
+  )
+  t.is(fsrs.advanceCards(cards, 100, { maximumRetrievability: 0.905 }).length, 1)
+  t.throws(() => fsrs.advanceCards([{ ...cards[0], memory: memory(0) }], 0), { message: /stability/ })
+  const ancient = { ...cards[0], lastReview: -9e18, due: 9e18 }
+  t.throws(() => fsrs.postponeCards([ancient], 9e18), { message: 'Card 1 is scheduled out of range' })
+  t.throws(() => fsrs.advanceCards([ancient], 9e17), { message: 'Card 1 is scheduled out of range' })
+})
+
+test('sorts cards into review order', (t) => {
+  const fsrs = new FSRS()
+  const stabilities = new Float32Array([10, 2, 50, 5])
+  const difficulties = new Float32Array([3, 8, 5, 1])
+  const elapsedDays = new Float32Array([12, 1, 30, 10])
+  const sort = (order: ReviewOrder, seed?: number) =>
+    Array.from(fsrs.sortCards(stabilities, difficulties, elapsedDays, order, { seed }))
+
+  t.deepEqual(sort(ReviewOrder.RetrievabilityAscending), [3, 0, 2, 1])
+  t.deepEqual(sort(ReviewOrder.RetrievabilityDescending), [1, 2, 0, 3])
+  t.deepEqual(sort(ReviewOrder.RelativeOverdueness), [3, 0, 2, 1])
+  t.deepEqual(sort(ReviewOrder.DifficultyAscending), [3, 0, 2, 1])
+  t.deepEqual(sort(ReviewOrder.DifficultyDescending), [1, 2, 0, 3])
+  t.deepEqual(sort(ReviewOrder.Random, 1), sort(ReviewOrder.Random, 1))
+  t.deepEqual([...sort(ReviewOrder.Random, 1)].sort(), [0, 1, 2, 3])
+  t.throws(() => fsrs.sortCards(stabilities, difficulties, new Float32Array(2), ReviewOrder.Random), {
+    message: /same length/,
+  })
+})
+
+test('computes next states for many cards at once', (t) => {
+  const fsrs = new FSRS()
+  const { intervals, stabilities, difficulties } = fsrs.nextStatesBatch(
+    new Float32Array([0, 20]),
+    new Float32Array([0, 5]),
+    new Float32Array([0, 20]),
+    0.9,
+  )
+  t.is(intervals.length, 8)
+  const expected = [fsrs.nextStates(null, 0.9, 0), fsrs.nextStates(new MemoryState(20, 5), 0.9, 20)]
+  expected.forEach((nextStates, card) => {
+    ;[nextStates.again, nextStates.hard, nextStates.good, nextStates.easy].forEach((state, button) => {
+      const i = card * 4 + button
+      t.is(intervals[i], Math.fround(state.interval))
+      t.is(stabilities[i], Math.fround(state.memory.stability))
+      t.is(difficulties[i], Math.fround(state.memory.difficulty))
+    })
+  })
+  t.throws(() => fsrs.nextStatesBatch(new Float32Array(1), new Float32Array(2), new Float32Array(1), 0.9), {
+    message: /same length/,
+  })
+  const batch = (stability: number, difficulty: number) =>
+    fsrs.nextStatesBatch(new Float32Array([20, stability]), new Float32Array([5, difficulty]), new Float32Array(2), 0.9)
+  t.throws(() => batch(NaN, 5), { message: 'stabilities[1] must be 0 or a positive number' })
+  t.throws(() => batch(-1, 5), { message: 'stabilities[1] must be 0 or a positive number' })
+  t.throws(() => batch(20, 0), { message: 'difficulties[1] must be between 1 and 10' })
+  t.throws(() => batch(20, Infinity), { message: 'difficulties[1] must be between 1 and 10' })
+})
+
+test('runs concurrent tasks on one instance', async (t) => {
+  const fsrs = new FSRS()
+  const items = [
+    new FSRSItem([new FSRSReview(3, 0), new FSRSReview(3, 1)]),
+    new FSRSItem([new FSRSReview(1, 0), new FSRSReview(3, 1), new FSRSReview(3, 3)]),
+  ]
+  const pending = Array.from({ length: 4 }, () => fsrs.evaluateAsync(items))
+  const interval = fsrs.nextStates(null, 0.9, 0).good.interval
+  const results = await Promise.all(pending)
+  t.true(results.every(({ logLoss }) => logLoss === results[0].logLoss))
+  t.is(fsrs.nextStates(null, 0.9, 0).good.interval, interval)
+})
+
+test('exports a model for worker threads', (t) => {
+  const fsrs = new FSRS([0.4, 0.9, 2.3, 10.9, 4.93, 0.94, 0.86, 0.01, 1.49, 0.14, 0.94, 2.18, 0.05, 0.34, 1.26, 0.29, 2.61])
+  const buffer = fsrs.toBuffer()
+  t.is(buffer.length, 84)
+
+  // Messages to a worker are structured clones, which turn the buffer into a Uint8Array
+  const copy = FSRS.fromBuffer(structuredClone(buffer))
+  const memoryState = new MemoryState(20, 5)
+  t.deepEqual(copy.nextStates(memoryState, 0.9, 10).toJSON(), fsrs.nextStates(memoryState, 0.9, 10).toJSON())
+  t.throws(() => FSRS.fromBuffer(new Uint8Array(3)), { message: /21 parameters/ })
+})
+
+test('reads and replaces the parameters of a model', (t) => {
+  const fsrs = new FSRS()
+  t.deepEqual(fsrs.parameters, DEFAULT_PARAMETERS.map(Math.fround))
+
+  const memoryState = new MemoryState(20, 5)
+  const fsrs5 = [0.4, 0.9, 2.3, 10.9, 4.93, 0.94, 0.86, 0.01, 1.49, 0.14, 0.94, 2.18, 0.05, 0.34, 1.26, 0.29, 2.61]
+  fsrs.setParameters(fsrs5)
+  t.is(fsrs.parameters.length, 21)
+  t.is(fsrs.parameters[20], 0.5)
+  t.deepEqual(fsrs.nextStates(memoryState, 0.9, 10).toJSON(), new FSRS(fsrs5).nextStates(memoryState, 0.9, 10).toJSON())
+
+  t.throws(() => fsrs.setParameters([1, 2]), { message: /InvalidParameters/ })
+  t.is(fsrs.parameters[20], 0.5)
+
+  const outOfRange = [...DEFAULT_PARAMETERS]
+  outOfRange[0] = 0
+  outOfRange[16] = 50
+  fsrs.setParameters(outOfRange)
+  t.is(fsrs.parameters[0], Math.fround(0.001))
+  t.is(fsrs.parameters[16], 6)
+})
+
+test('names the parameters and their bounds', (t) => {
+  const parameters = parametersFromArray(DEFAULT_PARAMETERS)
+  t.is(parameters.initialStabilityGood, Math.fround(DEFAULT_PARAMETERS[2]))
+  t.is(parameters.decay, Math.fround(FSRS6_DEFAULT_DECAY))
+  t.deepEqual(parametersToArray(parameters), DEFAULT_PARAMETERS.map(Math.fround))
+  t.is(parametersFromArray([]).shortTermStabilityFactor, Math.fround(DEFAULT_PARAMETERS[17]))
+  t.throws(() => parametersFromArray([1]), { message: /InvalidParameters/ })
+  t.throws(() => parametersToArray({ ...parameters, easyBonus: NaN }), { message: 'easyBonus must be a finite number' })
+  t.throws(() => parametersToArray({ ...parameters, decay: Infinity }), { message: 'decay must be a finite number' })
+
+  const info = parameterInfo()
+  t.is(info.length, 21)
+  t.like(info[17], { name: 'shortTermStabilityFactor', index: 17 })
+  t.like(info[20], { name: 'decay', min: 0.1, max: 0.8, defaultValue: 0.1542 })
+  t.deepEqual(
+    info.map(({ defaultValue }) => Math.fround(defaultValue)),
+    DEFAULT_PARAMETERS.map(Math.fround),
+  )
+  for (const { name, min, max, defaultValue } of info) {
+    t.true(name in parameters)
+    t.true(defaultValue >= min && defaultValue <= max)
+  }
+})
@@ -1,6 +1,4 @@
-import { promises as fs } from 'fs'
-import { parseString } from '@fast-csv/parse'
-import { FSRSItem, FSRSReview, FSRS } from '../index.js'
+import { FSRSItem, FSRS } from '../index.js'
 
 function progress(enableShortTerm, err, progressValue) {
   if (err) {
@@ -14,32 +12,14 @@ async function main() {
   // read revlog.csv
   // please download from
   // https://github.com/open-spaced-repetition/fsrs-rs/files/15046782/revlog.csv
-  const content = await fs.readFile('./revlog.csv')
-  const records = await new Promise((resolve, reject) => {
-    const results = []
-    parseString(content, {
-      headers: true,
-      ignoreEmpty: true,
-      trim: true,
-    })
-      .on('data', (row) => results.push(row))
-      .on('error', (error) => reject(error))
-      .on('end', () => resolve(results))
-  })
-
-  console.log(`revlogs.len() = ${records.length}`)
   console.time('full training time')
 
-  // group by card_id
-  const reviewsByCard = groupReviewsByCard(records)
-
-  // convert to FSRSItems
-  const fsrsItems = Object.values(reviewsByCard)
-    .map(removeRevlogBeforeLastLearning)
-    .filter((history) => history.length > 0)
-    .flatMap(convertToFSRSItem)
-    .sort((a, b) => a[0] - b[0])
-    .map((item) => item[1])
+  // group by card_id, drop reviews before the last learning block and
+  // convert to FSRSItems. next day starts at 4:00:00 UTC+8
+  const { items: fsrsItems, cardIds } = FSRSItem.fromRevlogCsv('./revlog.csv', {
+    nextDayStartsAt: 4,
+    utcOffsetMinutes: 8 * 60,
+  })
   console.log(`fsrs_items.len() = ${fsrsItems.length}`)
 
   async function computeParametersWrapper(enableShortTerm) {
@@ -48,6 +28,7 @@ async function main() {
     const optimizedParameters = await fsrs.computeParameters(fsrsItems, {
       enableShortTerm,
       numRelearningSteps: 1,
+      cardIds,
       progress: progress.bind(null, enableShortTerm),
       timeout: 1000 /** 1s */,
     })
@@ -62,75 +43,4 @@ async function main() {
   console.timeEnd('full training time')
 }
 
-function removeRevlogBeforeLastLearning(entries) {
-  const isLearningState = (entry) => [0 /** New */, 1 /** Learning */].includes(entry[2 /** review_state */])
-
-  let lastLearningBlockStart = -1
-  for (let i = entries.length - 1; i >= 0; i--) {
-    if (isLearningState(entries[i])) {
-      lastLearningBlockStart = i
-    } else if (lastLearningBlockStart !== -1) {
-      break
-    }
-  }
-
-  return lastLearningBlockStart !== -1 ? entries.slice(lastLearningBlockStart) : []
-}
-
-function groupReviewsByCard(records) {
-  const reviewsByCard = {}
-
-  records.forEach((record) => {
-    const cardId = record.card_id
-    if (!reviewsByCard[cardId]) {
-      reviewsByCard[cardId] = []
-    }
-
-    // convert unix timestamp (ms) to Date object
-    // next day start at 4:00:00 UTC+8
-    // use review_rating as rating
-    const timestamp = parseInt(record.review_time)
-    const date = new Date(timestamp)
-
-    // convert to UTC+8 first
-    date.setTime(date.getTime() + 8 * 60 * 60 * 1000)
-
-    // then subtract 4 hours for next day cutoff
-    date.setTime(date.getTime() - 4 * 60 * 60 * 1000)
-    reviewsByCard[cardId].push([date, parseInt(record.review_rating), parseInt(record.review_state)])
-  })
-
-  // ensure reviews for each card are sorted by time
-  Object.values(reviewsByCard).forEach((reviews) => {
-    reviews.sort((a, b) => a[0] - b[0])
-  })
-
-  return reviewsByCard
-}
-
-function convertToFSRSItem(history) {
-  const reviews = []
-  let lastDate = history[0][0]
-  const items = []
-
-  for (const [date, rating] of history) {
-    const deltaT = dateDiffInDays(lastDate, date)
-    reviews.push(new FSRSReview(rating, deltaT))
-    if (deltaT > 0) {
-      // the last review is not the same day
-      items.push([date, new FSRSItem([...reviews])])
-    }
-    lastDate = date
-  }
-
-  return items.filter((item) => item[1].longTermReviewCnt() > 0)
-}
-
-function dateDiffInDays(a, b) {
-  const _MS_PER_DAY = 1000 * 60 * 60 * 24
-  const utc1 = Date.UTC(a.getUTCFullYear(), a.getUTCMonth(), a.getUTCDate())
-  const utc2 = Date.UTC(b.getUTCFullYear(), b.getUTCMonth(), b.getUTCDate())
-  return Math.floor((utc2 - utc1) / _MS_PER_DAY)
-}
-
 main()
@@ -310,8 +310,12 @@ if (!nativeBinding) {
   throw new Error(`Failed to load native binding`)
 }
 
-const { FSRS, FSRS5_DEFAULT_DECAY, FSRS6_DEFAULT_DECAY, DEFAULT_PARAMETERS, FSRSReview, FSRSItem, MemoryState, NextStates, ItemState, FilterOutlierResult, defaultSimulatorConfig, simulate, evaluateWithTimeSeriesSplits, filterOutlier, checkAndFillParameters } = nativeBinding
+const { Dataset, ReviewOrder, CardState, Scheduler, FSRS, FSRS5_DEFAULT_DECAY, FSRS6_DEFAULT_DECAY, DEFAULT_PARAMETERS, FSRSReview, FSRSItem, MemoryState, NextStates, ItemState, RevlogImportResult, FilterOutlierResult, fuzzRange, fuzzInterval, loadBalanceInterval, rescheduleCards, defaultSimulatorConfig, simulate, compareEasyDays, simulateAsync, computeOptimalRetention, evaluateWithTimeSeriesSplits, evaluateWithTimeSeriesSplitsAsync, filterOutlier, checkAndFillParameters, parametersFromArray, parametersToArray, parameterInfo } = nativeBinding
 
+module.exports.Dataset = Dataset
+module.exports.ReviewOrder = ReviewOrder
+module.exports.CardState = CardState
+module.exports.Scheduler = Scheduler
 module.exports.FSRS = FSRS
 module.exports.FSRS5_DEFAULT_DECAY = FSRS5_DEFAULT_DECAY
 module.exports.FSRS6_DEFAULT_DECAY = FSRS6_DEFAULT_DECAY
@@ -321,9 +325,21 @@ module.exports.FSRSItem = FSRSItem
 module.exports.MemoryState = MemoryState
 module.exports.NextStates = NextStates
 module.exports.ItemState = ItemState
+module.exports.RevlogImportResult = RevlogImportResult
 module.exports.FilterOutlierResult = FilterOutlierResult
+module.exports.fuzzRange = fuzzRange
+module.exports.fuzzInterval = fuzzInterval
+module.exports.loadBalanceInterval = loadBalanceInterval
+module.exports.rescheduleCards = rescheduleCards
 module.exports.defaultSimulatorConfig = defaultSimulatorConfig
 module.exports.simulate = simulate
+module.exports.compareEasyDays = compareEasyDays
+module.exports.simulateAsync = simulateAsync
+module.exports.computeOptimalRetention = computeOptimalRetention
 module.exports.evaluateWithTimeSeriesSplits = evaluateWithTimeSeriesSplits
+module.exports.evaluateWithTimeSeriesSplitsAsync = evaluateWithTimeSeriesSplitsAsync
 module.exports.filterOutlier = filterOutlier
 module.exports.checkAndFillParameters = checkAndFillParameters
+module.exports.parametersFromArray = parametersFromArray
+module.exports.parametersToArray = parametersToArray
+module.exports.parameterInfo = parameterInfo
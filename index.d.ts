@@ -3,6 +3,182 @@
 
 /* auto-generated by NAPI-RS */
 
+/** The FSRS-6 parameters by name. Stabilities are in days. */
+export interface Parameters {
+  /** w0 */
+  initialStabilityAgain: number
+  /** w1 */
+  initialStabilityHard: number
+  /** w2 */
+  initialStabilityGood: number
+  /** w3 */
+  initialStabilityEasy: number
+  /** w4 */
+  initialDifficulty: number
+  /** w5 */
+  initialDifficultyRatingFactor: number
+  /** w6 */
+  difficultyDelta: number
+  /** w7 */
+  difficultyMeanReversion: number
+  /** w8 */
+  recallStabilityFactor: number
+  /** w9 */
+  recallStabilityDecay: number
+  /** w10 */
+  recallRetrievabilityFactor: number
+  /** w11 */
+  forgetStabilityFactor: number
+  /** w12 */
+  forgetDifficultyExponent: number
+  /** w13 */
+  forgetStabilityExponent: number
+  /** w14 */
+  forgetRetrievabilityFactor: number
+  /** w15 */
+  hardPenalty: number
+  /** w16 */
+  easyBonus: number
+  /** w17 */
+  shortTermStabilityFactor: number
+  /** w18 */
+  shortTermRatingOffset: number
+  /** w19 */
+  shortTermStabilityExponent: number
+  /** w20 */
+  decay: number
+}
+/** What a parameter means, and the range `new FSRS()` clips it to. */
+export interface ParameterInfo {
+  /** The name of the field in {@link Parameters}. */
+  name: string
+  /** The position in the flat array. */
+  index: number
+  /**
+   * The lower bound. For `shortTermStabilityExponent` it is 0.01 rather than 0 when
+   * training with short-term scheduling enabled.
+   */
+  min: number
+  /**
+   * The upper bound. For `shortTermStabilityFactor` and `shortTermRatingOffset` it
+   * only holds for a single relearning step; with more, training lowers it depending on
+   * the forget parameters.
+   */
+  max: number
+  defaultValue: number
+  description: string
+}
+/**
+ * A scheduled card for {@link FSRS.postponeCards} and {@link FSRS.advanceCards}. Days
+ * are counted from any fixed day, the same one as `today`.
+ */
+export interface DueCard {
+  id: number
+  memory: MemoryStateJson
+  lastReview: number
+  due: number
+}
+export interface PostponeOption {
+  /** Postpone at most this many cards. */
+  count?: number
+  /** Only postpone cards whose retrievability on the new due day is at least this high. */
+  minimumRetrievability?: number
+}
+export interface AdvanceOption {
+  /** Advance at most this many cards. */
+  count?: number
+  /** Only advance cards whose retrievability today is at most this high. */
+  maximumRetrievability?: number
+}
+export interface DueChange {
+  id: number
+  due: number
+  /** The retrievability on the new due day. */
+  retrievability: number
+}
+/** The order of {@link FSRS.sortCards}. */
+export const enum ReviewOrder {
+  /** The cards most likely to be forgotten first. */
+  RetrievabilityAscending = 'RetrievabilityAscending',
+  RetrievabilityDescending = 'RetrievabilityDescending',
+  /** The cards overdue by the largest share of their interval first. */
+  RelativeOverdueness = 'RelativeOverdueness',
+  DifficultyAscending = 'DifficultyAscending',
+  DifficultyDescending = 'DifficultyDescending',
+  /** Shuffled by `seed`. */
+  Random = 'Random'
+}
+export interface ReviewOrderOption {
+  /**
+   * The retention intervals are scheduled for, used by `RelativeOverdueness`.
+   * Defaults to 0.9.
+   */
+  desiredRetention?: number
+  /** Seeds the `Random` order. Defaults to 0. */
+  seed?: number
+}
+export const enum CardState {
+  New = 'New',
+  Learning = 'Learning',
+  Review = 'Review',
+  Relearning = 'Relearning'
+}
+/** The scheduling state of a card. Timestamps are unix milliseconds. */
+export interface Card {
+  /** Seeds the interval fuzz, see {@link SchedulerOptions.enableFuzz}. */
+  id?: number
+  state: CardState
+  /** The current learning or relearning step, unset in the other states. */
+  step?: number
+  /** Unset until the first review. */
+  stability?: number
+  /** Unset until the first review. */
+  difficulty?: number
+  due: number
+  lastReview?: number
+  /** Days from the last review to `due` while in review, 0 during (re)learning. */
+  scheduledDays: number
+  reps: number
+  lapses: number
+}
+/** A record of one review, with the card as it was before the review. */
+export interface ReviewLog {
+  rating: number
+  state: CardState
+  due: number
+  stability?: number
+  difficulty?: number
+  /** Whole days since the previous review. */
+  elapsedDays: number
+  /** The interval the review scheduled, 0 if the card is in (re)learning. */
+  scheduledDays: number
+  reviewTime: number
+}
+export interface ReviewResult {
+  card: Card
+  log: ReviewLog
+}
+export interface SchedulerOptions {
+  /** Defaults to 0.9. */
+  desiredRetention?: number
+  /** The longest interval in days. Defaults to 36500. */
+  maximumInterval?: number
+  /**
+   * Delays in minutes between the reviews of a new card before it graduates to
+   * day intervals. Defaults to `[1, 10]`. Empty to graduate on the first review.
+   */
+  learningSteps?: Array<number>
+  /**
+   * Delays in minutes between the reviews of a lapsed card before it returns to
+   * day intervals. Defaults to `[10]`. Empty to keep lapsed cards in review.
+   */
+  relearningSteps?: Array<number>
+  /**
+   * Spread day intervals with {@link fuzzInterval}, seeded by the card id and its number
+   * of reviews. Defaults to false.
+   */
+  enableFuzz?: boolean
+}
 export const FSRS5_DEFAULT_DECAY: number
 export const FSRS6_DEFAULT_DECAY: number
 /**
@@ -13,6 +189,38 @@ export const FSRS6_DEFAULT_DECAY: number
  * to fix this, the value can be extracted to a `const` and then used.
  */
 export const DEFAULT_PARAMETERS: number[]
+/** Plain-object form of {@link FSRSReview}. */
+export interface FSRSReviewJson {
+  rating: number
+  deltaT: number
+}
+/** Plain-object form of {@link FSRSItem}. */
+export interface FSRSItemJson {
+  reviews: Array<FSRSReviewJson>
+}
+/** The result of {@link FSRS.nextStatesBatch}, four entries per card. */
+export interface NextStatesBatch {
+  intervals: Float32Array
+  stabilities: Float32Array
+  difficulties: Float32Array
+}
+/** Plain-object form of {@link MemoryState}. */
+export interface MemoryStateJson {
+  stability: number
+  difficulty: number
+}
+/** Plain-object form of {@link ItemState}. */
+export interface ItemStateJson {
+  memory: MemoryStateJson
+  interval: number
+}
+/** Plain-object form of {@link NextStates}. */
+export interface NextStatesJson {
+  again: ItemStateJson
+  hard: ItemStateJson
+  good: ItemStateJson
+  easy: ItemStateJson
+}
 export interface TrainingConfig {
   numEpochs?: number
   batchSize?: number
@@ -30,6 +238,22 @@ export interface SimulationResult {
   averageDesiredRetention?: number
   introducedCntPerDay: Array<number>
 }
+/** Totals of a simulation, for comparing scheduling settings. */
+export interface SimulationSummary {
+  /** Seconds spent over the whole simulation. */
+  totalCost: number
+  reviewCount: number
+  /** The share of reviews answered correctly. */
+  trueRetention: number
+  /** How many cards are remembered at the end of the simulation. */
+  memorized: number
+  /** Reviews on each day of the week, Monday first. */
+  reviewsPerWeekday: Array<number>
+}
+export interface EasyDaysComparison {
+  baseline: SimulationSummary
+  easyDays: SimulationSummary
+}
 export interface SimulatorConfig {
   deckSize: number
   learnSpan: number
@@ -46,6 +270,68 @@ export interface SimulatorConfig {
   learningStepCount: number
   relearningStepCount: number
   suspendAfterLapses?: number
+  /**
+   * Replaces the interval the simulator scheduled for a reviewed card, e.g. to apply
+   * fuzz or load balancing. Only supported by `simulateAsync` and `computeOptimalRetention`.
+   */
+  postSchedulingFn?: (context: PostSchedulingContext) => number
+  /**
+   * The order in which due cards are reviewed, lower values first. Only supported by
+   * `simulateAsync` and `computeOptimalRetention`.
+   */
+  reviewPriorityFn?: (card: SimulatorCard) => number
+  /**
+   * Load balance the scheduled intervals away from easy days. Can't be combined with
+   * `postSchedulingFn`.
+   */
+  easyDays?: EasyDays
+}
+/** A card as the simulator sees it. Days are counted from the start of the simulation. */
+export interface SimulatorCard {
+  /**
+   * The id of an {@link ExistingCard}. Cards introduced by the simulation have ids of 0
+   * and below.
+   */
+  id: number
+  difficulty: number
+  stability: number
+  lastDate: number
+  due: number
+  interval: number
+  lapses: number
+  desiredRetention: number
+}
+/**
+ * A card of an existing collection to start a simulation from. Days are relative to the
+ * first simulated day, so reviews in the past have a negative `lastDate`.
+ */
+export interface ExistingCard {
+  /**
+   * Passed to the simulator hooks. Must be 1 or more, as the simulation numbers the cards
+   * it introduces from 0 downwards.
+   */
+  id: number
+  stability: number
+  difficulty: number
+  lastDate: number
+  /** Overdue cards may have a negative due day, they are reviewed on the first day. */
+  due: number
+  /** Defaults to `due - lastDate`. */
+  interval?: number
+  lapses?: number
+  /** Defaults to the desired retention of the simulation. */
+  desiredRetention?: number
+}
+/** What `postSchedulingFn` gets to decide the interval of a card that was just reviewed. */
+export interface PostSchedulingContext {
+  /** The card, with the interval the simulator scheduled. */
+  card: SimulatorCard
+  maxInterval: number
+  today: number
+  /** The number of cards due on each day of the simulation. */
+  dueCountsPerDay: Array<number>
+  /** A number in [0, 1) from the simulation's seeded generator, e.g. for fuzz. */
+  random: number
 }
 export interface ModelEvaluation {
   logLoss: number
@@ -60,36 +346,350 @@ export interface ComputeParametersOption {
   cardIds?: Array<number>
   /** Optional optimizer hyperparameters */
   trainingConfig?: TrainingConfig
-  progress?: (err: Error | null , value: { current: number, total: number, percent: number }) => void
+  /**
+   * Called whenever training progress changes. While training, the value also
+   * says which split and epoch changed and how many items of that epoch are done.
+   * fsrs only exposes training progress as shared state, so it is checked every
+   * `timeout` milliseconds, and the training and validation loss are not reported.
+   */
+  progress?: (err: Error | null , value: { current: number, total: number, percent: number, splitIndex?: number, epoch?: number, epochTotal?: number, itemsProcessed?: number, itemsTotal?: number }) => void
+  /** How often, in milliseconds, training progress is checked for changes. Defaults to 500. */
   timeout?: number
+  /**
+   * Stops training between batches once aborted. The promise then rejects with
+   * an error whose `code` is `"Cancelled"` and `message` is `"AbortError"`.
+   * Honoured by `computeParameters` and `evaluateWithTimeSeriesSplitsAsync`.
+   */
+  signal?: AbortSignal
+}
+export interface EvaluateOption {
+  progress?: (err: Error | null , value: { current: number, total: number, percent: number }) => void
+}
+export interface OptimalRetentionOption {
+  /**
+   * Called before each retention the search evaluates, with the number evaluated so
+   * far as `current`. The search doesn't know in advance how many retentions it needs,
+   * so `total` and `percent` are 0.
+   */
+  progress?: (err: Error | null , value: { current: number, total: number, percent: number }) => void
+  /**
+   * Stops the search before the next retention once aborted. The promise then rejects
+   * with an error whose `code` is `"Cancelled"` and `message` is `"AbortError"`.
+   */
+  signal?: AbortSignal
+}
+/** The average cost per memorized card the simulator found for a desired retention. */
+export interface RetentionCost {
+  desiredRetention: number
+  cost: number
+}
+export interface OptimalRetention {
+  /** The evaluated desired retention with the lowest cost. */
+  desiredRetention: number
+  /** Every evaluated desired retention, in ascending order. */
+  costCurve: Array<RetentionCost>
+}
+/** A card to reschedule. Days are counted from any fixed day, the same one as `today`. */
+export interface RescheduleCard {
+  id: number
+  /** The card's review history, replayed with the new parameters. */
+  reviews: Array<FSRSReviewJson>
+  /** The memory state before the first of `reviews`, as in {@link FSRS.memoryState}. */
+  startingState?: MemoryStateJson
+  lastReview: number
+  due: number
+}
+export interface RescheduleOption {
+  /** Defaults to 36500. */
+  maximumInterval?: number
+  /**
+   * Spread the intervals with {@link fuzzInterval}, seeded by the card id and its
+   * number of reviews. Defaults to false.
+   */
+  enableFuzz?: boolean
+}
+export interface RescheduledCard {
+  id: number
+  memory: MemoryStateJson
+  /** Days from the last review to the new due day. */
+  interval: number
+  due: number
+  /** How many days the card moved, negative if it is now due earlier. */
+  daysMoved: number
+}
+export interface FuzzOption {
+  /** Together with `reviewCount`, seeds the fuzz so a card is always fuzzed the same way. */
+  cardId: number
+  /** How many times the card has been reviewed before. */
+  reviewCount: number
+  /** Defaults to 36500. */
+  maximumInterval?: number
+}
+export interface LoadBalanceOption {
+  /** The number of cards due on each day, starting with the day of the review. */
+  dueCounts: Uint32Array
+  /** Defaults to 36500. */
+  maximumInterval?: number
+  easyDays?: EasyDays
+}
+/**
+ * How much to review on each day of the week, so intervals are moved away from days
+ * with less time to study.
+ */
+export interface EasyDays {
+  /**
+   * Seven load multipliers, Monday first. 1 is a normal day, 0.5 counts the cards due
+   * as twice as many, and 0 avoids the day unless no other day is acceptable.
+   */
+  multipliers: Array<number>
+  /** The weekday of the day of the review, or of the first simulated day, 0 for Monday. */
+  weekday: number
+}
+/** The inclusive range of whole days an interval may be fuzzed to. */
+export interface FuzzRange {
+  min: number
+  max: number
 }
+/** Decides which day a review timestamp falls on. */
+export interface DayCutoffOption {
+  /** Hour of the day at which the next day starts. Defaults to 4. */
+  nextDayStartsAt?: number
+  /** Offset of the reviewer's time zone from UTC in minutes, e.g. 480 for UTC+8. Defaults to 0. */
+  utcOffsetMinutes?: number
+}
+export interface ReviewEvent {
+  cardId: number
+  /** Unix timestamp of the review in milliseconds */
+  timestampMs: number
+  /** 1-4 */
+  rating: number
+}
+/**
+ * Intervals below 2.5 days are only rounded. Longer ones are spread by 15% of the days
+ * between 2.5 and 7, 10% of the days between 7 and 20 and 5% of the days beyond, plus
+ * one day, but never to 1 day or to `elapsedDays` or less.
+ */
+export declare function fuzzRange(interval: number, elapsedDays: number, maximumInterval?: number | undefined | null): FuzzRange
+/**
+ * Round `interval` to a day within its {@link fuzzRange}, chosen deterministically
+ * from the card id and review count.
+ */
+export declare function fuzzInterval(interval: number, elapsedDays: number, options: FuzzOption): number
+/**
+ * The day within the {@link fuzzRange} of `interval` with the fewest due cards, where
+ * `dueCounts[i]` is the number of cards due `i` days from now, weighted by `easyDays`.
+ * Ties go to the day closest to `interval`, then to the earlier one.
+ */
+export declare function loadBalanceInterval(interval: number, elapsedDays: number, dueCounts: Uint32Array, maximumInterval?: number | undefined | null, easyDays?: EasyDays | undefined | null): number
+/**
+ * Replay the review histories of `cards` with `newParameters`, and schedule them from
+ * their last review with the resulting memory states. Cards are never rescheduled
+ * before `today`.
+ */
+export declare function rescheduleCards(cards: Array<RescheduleCard>, newParameters: Array<number>, desiredRetention: number, today: number, options?: RescheduleOption | undefined | null): Array<RescheduledCard>
 export declare function defaultSimulatorConfig(): SimulatorConfig
-export declare function simulate(w: Array<number>, desiredRetention: number, config?: SimulatorConfig | undefined | null, seed?: number | undefined | null): SimulationResult
-export declare function evaluateWithTimeSeriesSplits(trainSet: Array<FSRSItem>, options?: ComputeParametersOption): ModelEvaluation
-export declare function filterOutlier(datasetForInitialization: Array<FSRSItem>, trainset: Array<FSRSItem>): FilterOutlierResult
+/**
+ * Simulate reviewing a deck of `config.deckSize` cards over `config.learnSpan` days.
+ *
+ * `existingCards`, if given, are the deck's already-learned cards and count toward
+ * `deckSize`. The rest of the deck is introduced as new cards.
+ */
+export declare function simulate(w: Array<number>, desiredRetention: number, config?: SimulatorConfig | undefined | null, seed?: number | undefined | null, existingCards?: Array<ExistingCard> | undefined | null): SimulationResult
+/**
+ * Simulate `config` with its `easyDays` and with all multipliers set to 1, using the
+ * same seed, to show how they affect retention and cost. Both simulations are load
+ * balanced, so the difference is only due to the easy days.
+ */
+export declare function compareEasyDays(w: Array<number>, desiredRetention: number, config: SimulatorConfig, seed?: number | undefined | null): EasyDaysComparison
+/**
+ * Like {@link simulate}, but runs on a background thread, which lets the simulator call
+ * the `postSchedulingFn` and `reviewPriorityFn` hooks of the config.
+ */
+export declare function simulateAsync(w: Array<number>, desiredRetention: number, config?: SimulatorConfig | undefined | null, seed?: number | undefined | null, existingCards?: Array<ExistingCard> | undefined | null): Promise<SimulationResult>
+/**
+ * Find the desired retention that minimizes the simulated workload per memorized card.
+ *
+ * fsrs' search runs the simulator for a series of retentions between 0.70 and 0.95 on a
+ * background thread, narrowing in on the cheapest one.
+ */
+export declare function computeOptimalRetention(parameters: Array<number>, config?: SimulatorConfig | undefined | null, options?: OptimalRetentionOption | undefined | null): Promise<OptimalRetention>
+export declare function evaluateWithTimeSeriesSplits(trainSet: Array<FSRSItem> | Dataset, options?: ComputeParametersOption): ModelEvaluation
+/**
+ * Like {@link evaluateWithTimeSeriesSplits}, but runs on a background thread.
+ * `progress` is called once per finished split.
+ */
+export declare function evaluateWithTimeSeriesSplitsAsync(trainSet: Array<FSRSItem> | Dataset, options?: ComputeParametersOption): Promise<ModelEvaluation>
+export declare function filterOutlier(datasetForInitialization: Array<FSRSItem> | Dataset, trainset: Array<FSRSItem> | Dataset): FilterOutlierResult
 export declare function checkAndFillParameters(parameters: Array<number>): Array<number>
+/**
+ * Name the parameters. Like {@link checkAndFillParameters}, an empty array gives the
+ * defaults and older 17 or 19 parameter sets are converted.
+ */
+export declare function parametersFromArray(parameters: Array<number>): Parameters
+/**
+ * The flat array taken by [`new FSRS()`]{@link FSRS.constructor} and the other functions.
+ * Every field must be a finite number.
+ */
+export declare function parametersToArray(parameters: Parameters): Array<number>
+/** The name, bounds, default and meaning of each parameter, in array order. */
+export declare function parameterInfo(): Array<ParameterInfo>
+/**
+ * A list of `FSRSItem`s, and optionally their card ids, kept in native memory so it
+ * can be passed to training, evaluation and {@link filterOutlier} without converting
+ * every item from JS again on each call.
+ */
+export declare class Dataset {
+  /**
+   * `cardIds`, if given, must be aligned with `items`. It is then used as the
+   * `cardIds` option of the APIs the dataset is passed to.
+   */
+  constructor(items?: Array<FSRSItem> | undefined | null, cardIds?: Array<number> | undefined | null)
+  /**
+   * Build a dataset from flat typed arrays, in the layout of
+   * {@link FSRS.computeParametersFromArrays}.
+   */
+  static fromArrays(ratings: Uint32Array, deltaTs: Uint32Array, itemOffsets: Uint32Array, cardIds?: Array<number> | undefined | null): Dataset
+  get length(): number
+  get items(): Array<FSRSItem>
+  get cardIds(): Array<number> | null
+  /** Append items. `cardIds` must be given exactly when the dataset has card ids. */
+  push(items: Array<FSRSItem>, cardIds?: Array<number> | undefined | null): void
+  /** Append all items of another dataset. */
+  extend(other: Dataset): void
+  /** A new dataset with the items for which `predicate` returns `true`. */
+  filter(predicate: (item: FSRSItem, index: number) => boolean): Dataset
+  /** A new dataset with the items at `indices`, in that order. */
+  select(indices: Uint32Array): Dataset
+  /** Split into the items before `index` and the items from `index` on. */
+  splitAt(index: number): [Dataset, Dataset]
+}
+/**
+ * Schedules cards through the New, Learning, Review and Relearning states, using
+ * {@link FSRS.nextStates} for memory states and review intervals.
+ *
+ * New cards go through the learning steps and lapsed cards through the relearning steps.
+ * Again returns a card to the first step, Hard repeats the current step and Good moves
+ * to the next one, until Good on the last step or Easy graduates it to day intervals.
+ */
+export declare class Scheduler {
+  /** `parameters` are handled like in [`new FSRS()`]{@link FSRS.constructor}. */
+  constructor(parameters?: Array<number> | undefined | null, options?: SchedulerOptions | undefined | null)
+  get desiredRetention(): number
+  get maximumInterval(): number
+  get enableFuzz(): boolean
+  get learningSteps(): Array<number>
+  get relearningSteps(): Array<number>
+  /**
+   * The default simulator config with the step counts and maximum interval of this
+   * scheduler, so simulations graduate cards by the same rules.
+   */
+  simulatorConfig(): SimulatorConfig
+  /** A card that has never been reviewed, due at `now`. */
+  newCard(now: number, id?: number | undefined | null): Card
+  /**
+   * Review `card` with `rating` (1-4) at `reviewTime`, and return the updated card
+   * together with a log entry for the review.
+   */
+  review(card: Card, rating: number, reviewTime: number): ReviewResult
+}
 export declare class FSRS {
   /**
    * - Parameters must be provided before running commands that need them.
    * - Parameters may be an empty array to use the default values instead.
    */
   constructor(parameters?: Array<number> | undefined | null)
+  /**
+   * The 21 parameters of the model, with defaults filled in for an empty array, older
+   * 17 or 19 parameter sets converted, and each clipped to its {@link parameterInfo}
+   * bounds like the model does.
+   */
+  get parameters(): Array<number>
+  /**
+   * Replace the parameters, checked like in [`new FSRS()`]{@link constructor}. If they
+   * are invalid, the model is left unchanged. Running background tasks keep using the
+   * previous parameters.
+   */
+  setParameters(parameters: Array<number>): void
+  /**
+   * The model as a compact buffer of 21 little-endian 32-bit floats, e.g. to post to a
+   * `worker_threads` worker, which rebuilds it with {@link fromBuffer}.
+   */
+  toBuffer(): Buffer
+  /** Rebuild a model exported with {@link toBuffer}. */
+  static fromBuffer(buffer: Uint8Array): Fsrs
   /** Calculate appropriate parameters for the provided review history. */
-  computeParameters(trainSet: Array<FSRSItem>, options?: ComputeParametersOption): Promise<Array<number>>
+  computeParameters(trainSet: Array<FSRSItem> | Dataset, options?: ComputeParametersOption): Promise<Array<number>>
+  /**
+   * Like {@link computeParameters}, but takes the review history as flat typed arrays,
+   * which avoids creating a JS object per review for large collections.
+   *
+   * Item `i` consists of the reviews from `itemOffsets[i]` up to (excluding)
+   * `itemOffsets[i + 1]`, so `itemOffsets` starts with 0, ends with the number of
+   * reviews, increases strictly and has one more entry than there are items.
+   */
+  computeParametersFromArrays(ratings: Uint32Array, deltaTs: Uint32Array, itemOffsets: Uint32Array, options?: ComputeParametersOption): Promise<Array<number>>
   /**
    * The intervals and memory states for each answer button.
    *
    * Parameters must have been provided when calling [`new FSRS()`]{@link constructor}.
+   *
+   * With `fuzz`, the intervals are rounded to whole days and spread with {@link fuzzInterval}.
+   * With `loadBalance`, they are instead moved to the least busy day with
+   * {@link loadBalanceInterval}, which already picks a day within the fuzz range, so
+   * `fuzz` and `loadBalance` can't be combined.
+   */
+  nextStates(currentMemoryState: MemoryState | undefined | null, desiredRetention: number, daysElapsed: number, fuzz?: FuzzOption | undefined | null, loadBalance?: LoadBalanceOption | undefined | null): NextStates
+  /**
+   * Like {@link nextStates} for many cards at once, without creating objects per card.
+   *
+   * Cards with a stability of 0 are new, the difficulty of others must be between 1 and
+   * 10. Fractions of `elapsedDays` are dropped. The results for card `i` are at `4 * i`
+   * to `4 * i + 3`, for Again, Hard, Good and Easy.
+   */
+  nextStatesBatch(stabilities: Float32Array, difficulties: Float32Array, elapsedDays: Float32Array, desiredRetention: number): NextStatesBatch
+  /** With `fuzz`, the interval is rounded to whole days and spread with {@link fuzzInterval}. */
+  nextInterval(stability: number | undefined | null, desiredRetention: number, rating: number, fuzz?: FuzzOption | undefined | null): number
+  benchmark(trainSet: Array<FSRSItem> | Dataset, options?: ComputeParametersOption): Array<number>
+  /** Like {@link benchmark}, but runs on a background thread and resolves with the parameters. */
+  benchmarkAsync(trainSet: Array<FSRSItem> | Dataset, options?: ComputeParametersOption): Promise<Array<number>>
+  /**
+   * Determine how well the model and parameters predict performance. The items of a
+   * {@link Dataset} with `cardIds` are evaluated per card, like in training.
+   *
+   * Parameters must have been provided when calling [`new FSRS()`]{@link constructor}.
    */
-  nextStates(currentMemoryState: MemoryState | undefined | null, desiredRetention: number, daysElapsed: number): NextStates
-  nextInterval(stability: number | undefined | null, desiredRetention: number, rating: number): number
-  benchmark(trainSet: Array<FSRSItem>, options?: ComputeParametersOption): Array<number>
+  evaluate(trainSet: Array<FSRSItem> | Dataset): ModelEvaluation
   /**
-   * Determine how well the model and parameters predict performance.
+   * Like {@link evaluate}, but runs on a background thread so the event loop stays responsive.
    *
    * Parameters must have been provided when calling [`new FSRS()`]{@link constructor}.
    */
-  evaluate(trainSet: Array<FSRSItem>): ModelEvaluation
+  evaluateAsync(trainSet: Array<FSRSItem> | Dataset, options?: EvaluateOption): Promise<ModelEvaluation>
+  /**
+   * The probability of recalling a card `daysElapsed` days after its last review,
+   * according to the forgetting curve of this model's decay.
+   */
+  currentRetrievability(memoryState: MemoryState, daysElapsed: number): number
+  /**
+   * Sample the forgetting curve of a memory state at each of the given day offsets,
+   * as {@link currentRetrievability} would for each of them.
+   */
+  forgettingCurve(memoryState: MemoryState, daysElapsed: Array<number>): Array<number>
+  /**
+   * Select cards due by `today` to postpone, those losing the least retrievability
+   * first. Each card is postponed by 5% of its interval, and at least a day.
+   */
+  postponeCards(cards: Array<DueCard>, today: number, options?: PostponeOption | undefined | null): Array<DueChange>
+  /**
+   * Select cards that are not due yet to review `today` instead, e.g. before a trip,
+   * those with the lowest retrievability first, as reviewing them early wastes the least.
+   */
+  advanceCards(cards: Array<DueCard>, today: number, options?: AdvanceOption | undefined | null): Array<DueChange>
+  /**
+   * The order in which to review cards, as indices into the arrays, which hold the
+   * memory state of each card and the days since its last review.
+   */
+  sortCards(stabilities: Float32Array, difficulties: Float32Array, elapsedDays: Float32Array, order: ReviewOrder, options?: ReviewOrderOption | undefined | null): Uint32Array
   /**
    * If a card has incomplete learning history, memory state can be approximated from
    * current sm2 values.
@@ -120,7 +720,9 @@ export declare class FSRSReview {
    * `delta_t` for item first(initial) review must be 0
    */
   get deltaT(): number
-  toJSON(): string
+  toJSON(): FSRSReviewJson
+  /** Restore a value returned by {@link toJSON}, e.g. after `JSON.parse`. */
+  static fromJSON(json: FSRSReviewJson): FSRSReview
 }
 /**
  * Stores a list of reviews for a card, in chronological order. Each FSRSItem corresponds
@@ -135,27 +737,62 @@ export declare class FSRSItem {
   get reviews(): Array<FSRSReview>
   set reviews(reviews: Array<FSRSReview>)
   longTermReviewCnt(): number
-  toJSON(): string
+  /**
+   * Read an Anki-style revlog CSV (a file path or its contents) and convert it into
+   * items sorted by review time, ready for {@link FSRS.computeParameters}.
+   *
+   * The CSV needs `card_id`, `review_time` (unix ms) and `review_rating` columns.
+   * If it has a `review_state` column, reviews before each card's last learning block
+   * are dropped. Reviews with a rating outside 1-4 are ignored.
+   */
+  static fromRevlogCsv(source: string | Buffer, options?: DayCutoffOption | undefined | null): RevlogImportResult
+  /**
+   * Convert timestamped reviews of any number of cards into items sorted by review time.
+   *
+   * Reviews of a card on the same day get a `deltaT` of 0, and an item is only produced
+   * for a review on a new day, so every item has a {@link longTermReviewCnt} of at least 1.
+   */
+  static fromReviewEvents(events: Array<ReviewEvent>, options?: DayCutoffOption | undefined | null): RevlogImportResult
+  toJSON(): FSRSItemJson
+  /** Restore a value returned by {@link toJSON}, e.g. after `JSON.parse`. */
+  static fromJSON(json: FSRSItemJson): FSRSItem
 }
 export declare class MemoryState {
   constructor(stability: number, difficulty: number)
   get stability(): number
   get difficulty(): number
-  toJSON(): string
+  toJSON(): MemoryStateJson
+  /** Restore a value returned by {@link toJSON}, e.g. after `JSON.parse`. */
+  static fromJSON(json: MemoryStateJson): MemoryState
 }
 export declare class NextStates {
   get hard(): ItemState
   get good(): ItemState
   get easy(): ItemState
   get again(): ItemState
-  toJSON(): string
+  toJSON(): NextStatesJson
+  /** Restore a value returned by {@link toJSON}, e.g. after `JSON.parse`. */
+  static fromJSON(json: NextStatesJson): NextStates
 }
 export declare class ItemState {
   get memory(): MemoryState
   get interval(): number
-  toJSON(): string
+  toJSON(): ItemStateJson
+  /** Restore a value returned by {@link toJSON}, e.g. after `JSON.parse`. */
+  static fromJSON(json: ItemStateJson): ItemState
+}
+export declare class RevlogImportResult {
+  get items(): Array<FSRSItem>
+  /** The card id of each item, to pass as `cardIds` to {@link FSRS.computeParameters}. */
+  get cardIds(): Array<number>
+  /** The items and their card ids as a {@link Dataset}. */
+  toDataset(): Dataset
 }
+/**
+ * Each filtered list is a {@link Dataset}, with the card ids of the kept items, when a
+ * `Dataset` was passed in for it.
+ */
 export declare class FilterOutlierResult {
-  get datasetForInitialization(): Array<FSRSItem>
-  get trainset(): Array<FSRSItem>
+  get datasetForInitialization(): Array<FSRSItem> | Dataset
+  get trainset(): Array<FSRSItem> | Dataset
 }
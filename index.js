@@ -5,6 +5,10 @@ const require = createRequire(import.meta.url)
 const {
   FSRS,
   DEFAULT_PARAMETERS,
+  Dataset,
+  ReviewOrder,
+  CardState,
+  Scheduler,
   FSRS5_DEFAULT_DECAY,
   FSRS6_DEFAULT_DECAY,
   FSRSReview,
@@ -12,17 +16,33 @@ const {
   MemoryState,
   NextStates,
   ItemState,
+  RevlogImportResult,
   FilterOutlierResult,
+  fuzzRange,
+  fuzzInterval,
+  loadBalanceInterval,
+  rescheduleCards,
   defaultSimulatorConfig,
   simulate,
+  compareEasyDays,
+  simulateAsync,
+  computeOptimalRetention,
   evaluateWithTimeSeriesSplits,
+  evaluateWithTimeSeriesSplitsAsync,
   filterOutlier,
   checkAndFillParameters,
+  parametersFromArray,
+  parametersToArray,
+  parameterInfo,
 } = require('./index.cjs')
 
 export {
   FSRS,
   DEFAULT_PARAMETERS,
+  Dataset,
+  ReviewOrder,
+  CardState,
+  Scheduler,
   FSRS5_DEFAULT_DECAY,
   FSRS6_DEFAULT_DECAY,
   FSRSReview,
@@ -30,10 +50,22 @@ export {
   MemoryState,
   NextStates,
   ItemState,
+  RevlogImportResult,
   FilterOutlierResult,
+  fuzzRange,
+  fuzzInterval,
+  loadBalanceInterval,
+  rescheduleCards,
   defaultSimulatorConfig,
   simulate,
+  compareEasyDays,
+  simulateAsync,
+  computeOptimalRetention,
   evaluateWithTimeSeriesSplits,
+  evaluateWithTimeSeriesSplitsAsync,
   filterOutlier,
   checkAndFillParameters,
+  parametersFromArray,
+  parametersToArray,
+  parameterInfo,
 }
@@ -0,0 +1,237 @@
+use napi::bindgen_prelude::{ClassInstance, Either, Env, JsFunction, Result, Uint32Array};
+use napi::{JsBoolean, JsUnknown};
+use napi_derive::napi;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+
+use crate::{FSRSItem, items_from_arrays, napi_error};
+
+/// Training data given either as JS `FSRSItem`s or as a native {@link Dataset}.
+pub(crate) type TrainSet<'a> = Either<Vec<&'a FSRSItem>, ClassInstance<Dataset>>;
+
+/// The items of a train set, together with the card ids stored in a `Dataset`. The items
+/// of a `Dataset` are shared rather than copied.
+pub(crate) fn train_set_to_fsrs(
+  train_set: TrainSet,
+) -> (Arc<Vec<fsrs::FSRSItem>>, Option<Vec<i64>>) {
+  match train_set {
+    Either::A(items) => (
+      Arc::new(items.into_iter().map(|item| item.0.clone()).collect()),
+      None,
+    ),
+    Either::B(dataset) => (Arc::clone(&dataset.items), dataset.card_ids.clone()),
+  }
+}
+
+/// The card ids of `kept`, a filtered copy of `items` that keeps equal items in their
+/// original order, like `fsrs::filter_outlier` does. `None` if an item of `kept` is not
+/// in `items`.
+pub(crate) fn kept_card_ids(
+  items: &[fsrs::FSRSItem],
+  card_ids: &[i64],
+  kept: &[fsrs::FSRSItem],
+) -> Option<Vec<i64>> {
+  let reviews = |item: &fsrs::FSRSItem| {
+    item
+      .reviews
+      .iter()
+      .map(|review| (review.rating, review.delta_t))
+      .collect::<Vec<_>>()
+  };
+  let mut card_ids_by_reviews = HashMap::<_, VecDeque<i64>>::new();
+  for (item, &card_id) in items.iter().zip(card_ids) {
+    card_ids_by_reviews
+      .entry(reviews(item))
+      .or_default()
+      .push_back(card_id);
+  }
+  kept
+    .iter()
+    .map(|item| {
+      card_ids_by_reviews
+        .get_mut(&reviews(item))
+        .and_then(VecDeque::pop_front)
+    })
+    .collect()
+}
+
+fn check_card_ids(items: usize, card_ids: Option<&Vec<i64>>) -> Result<()> {
+  if card_ids.is_some_and(|card_ids| card_ids.len() != items) {
+    return Err(napi_error("cardIds must have one entry per item"));
+  }
+  Ok(())
+}
+
+/// A list of `FSRSItem`s, and optionally their card ids, kept in native memory so it
+/// can be passed to training, evaluation and {@link filterOutlier} without converting
+/// every item from JS again on each call.
+#[napi(js_name = "Dataset")]
+#[derive(Debug, Default, Clone)]
+pub struct Dataset {
+  /// Shared with the tasks the dataset is passed to, and copied only when it is changed
+  /// while one of them still holds it.
+  pub(crate) items: Arc<Vec<fsrs::FSRSItem>>,
+  pub(crate) card_ids: Option<Vec<i64>>,
+}
+
+#[napi]
+impl Dataset {
+  /// `cardIds`, if given, must be aligned with `items`. It is then used as the
+  /// `cardIds` option of the APIs the dataset is passed to.
+  #[napi(constructor)]
+  pub fn new(items: Option<Vec<&FSRSItem>>, card_ids: Option<Vec<i64>>) -> Result<Self> {
+    let items = items
+      .unwrap_or_default()
+      .into_iter()
+      .map(|item| item.0.clone())
+      .collect::<Vec<_>>();
+    check_card_ids(items.len(), card_ids.as_ref())?;
+    Ok(Self::from_fsrs(items, card_ids))
+  }
+
+  /// Build a dataset from flat typed arrays, in the layout of
+  /// {@link FSRS.computeParametersFromArrays}.
+  #[napi]
+  pub fn from_arrays(
+    ratings: Uint32Array,
+    delta_ts: Uint32Array,
+    item_offsets: Uint32Array,
+    card_ids: Option<Vec<i64>>,
+  ) -> Result<Self> {
+    let items = items_from_arrays(&ratings, &delta_ts, &item_offsets)?;
+    check_card_ids(items.len(), card_ids.as_ref())?;
+    Ok(Self::from_fsrs(items, card_ids))
+  }
+
+  #[napi(getter)]
+  pub fn length(&self) -> u32 {
+    self.items.len() as u32
+  }
+
+  #[napi(getter)]
+  pub fn items(&self) -> Vec<FSRSItem> {
+    self.items.iter().cloned().map(FSRSItem).collect()
+  }
+
+  #[napi(getter)]
+  pub fn card_ids(&self) -> Option<Vec<i64>> {
+    self.card_ids.clone()
+  }
+
+  /// Append items. `cardIds` must be given exactly when the dataset has card ids.
+  #[napi]
+  pub fn push(&mut self, items: Vec<&FSRSItem>, card_ids: Option<Vec<i64>>) -> Result<()> {
+    self.extend_items(
+      items.into_iter().map(|item| item.0.clone()).collect(),
+      card_ids,
+    )
+  }
+
+  /// Append all items of another dataset.
+  #[napi]
+  pub fn extend(&mut self, other: &Dataset) -> Result<()> {
+    self.extend_items(other.items.to_vec(), other.card_ids.clone())
+  }
+
+  /// A new dataset with the items for which `predicate` returns `true`.
+  #[napi(ts_args_type = "predicate: (item: FSRSItem, index: number) => boolean")]
+  pub fn filter(&self, env: Env, predicate: JsFunction) -> Result<Dataset> {
+    let mut indices = Vec::new();
+    for (index, item) in self.items.iter().enumerate() {
+      let item = FSRSItem(item.clone()).into_instance(env)?;
+      let keep: JsBoolean = predicate
+        .call(
+          None,
+          &[
+            item.as_object(env).into_unknown(),
+            env.create_uint32(index as u32)?.into_unknown(),
+          ] as &[JsUnknown],
+        )?
+        .coerce_to_bool()?;
+      if keep.get_value()? {
+        indices.push(index);
+      }
+    }
+    Ok(self.select_indices(indices))
+  }
+
+  /// A new dataset with the items at `indices`, in that order.
+  #[napi]
+  pub fn select(&self, indices: Uint32Array) -> Result<Dataset> {
+    let indices = indices
+      .iter()
+      .map(|&index| index as usize)
+      .collect::<Vec<_>>();
+    if indices.iter().any(|&index| index >= self.items.len()) {
+      return Err(napi_error("indices must be less than the dataset length"));
+    }
+    Ok(self.select_indices(indices))
+  }
+
+  /// Split into the items before `index` and the items from `index` on.
+  #[napi(ts_return_type = "[Dataset, Dataset]")]
+  pub fn split_at(&self, index: u32) -> Result<Vec<Dataset>> {
+    let index = index as usize;
+    if index > self.items.len() {
+      return Err(napi_error("index must not exceed the dataset length"));
+    }
+    let (head, tail) = self.items.split_at(index);
+    let (head_card_ids, tail_card_ids) = match &self.card_ids {
+      Some(card_ids) => {
+        let (head, tail) = card_ids.split_at(index);
+        (Some(head.to_vec()), Some(tail.to_vec()))
+      }
+      None => (None, None),
+    };
+    Ok(vec![
+      Dataset::from_fsrs(head.to_vec(), head_card_ids),
+      Dataset::from_fsrs(tail.to_vec(), tail_card_ids),
+    ])
+  }
+}
+
+impl Dataset {
+  pub(crate) fn from_fsrs(
+    items: impl Into<Arc<Vec<fsrs::FSRSItem>>>,
+    card_ids: Option<Vec<i64>>,
+  ) -> Self {
+    Self {
+      items: items.into(),
+      card_ids,
+    }
+  }
+
+  fn extend_items(&mut self, items: Vec<fsrs::FSRSItem>, card_ids: Option<Vec<i64>>) -> Result<()> {
+    check_card_ids(items.len(), card_ids.as_ref())?;
+    match card_ids {
+      Some(card_ids) => {
+        if let Some(existing) = self.card_ids.as_mut() {
+          existing.extend(card_ids);
+        } else if self.items.is_empty() {
+          self.card_ids = Some(card_ids);
+        } else {
+          return Err(napi_error("this dataset has no cardIds"));
+        }
+      }
+      None if self.card_ids.is_some() => {
+        return Err(napi_error("cardIds are required for this dataset"));
+      }
+      None => {}
+    }
+    Arc::make_mut(&mut self.items).extend(items);
+    Ok(())
+  }
+
+  fn select_indices(&self, indices: Vec<usize>) -> Dataset {
+    Dataset::from_fsrs(
+      indices
+        .iter()
+        .map(|&index| self.items[index].clone())
+        .collect::<Vec<_>>(),
+      self
+        .card_ids
+        .as_ref()
+        .map(|card_ids| indices.iter().map(|&index| card_ids[index]).collect()),
+    )
+  }
+}
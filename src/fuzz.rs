@@ -0,0 +1,98 @@
+use crate::{EasyDays, FuzzOption};
+
+/// Anki's fuzz ranges: each day of the interval within `start..end` widens the range by `factor`.
+const FUZZ_RANGES: [(f64, f64, f64); 3] = [
+  (2.5, 7.0, 0.15),
+  (7.0, 20.0, 0.1),
+  (20.0, f64::INFINITY, 0.05),
+];
+
+pub(crate) const DEFAULT_MAXIMUM_INTERVAL: u32 = 36500;
+
+pub(crate) fn fuzz_bounds(interval: f64, elapsed_days: u32, maximum_interval: u32) -> (u32, u32) {
+  let maximum_interval = maximum_interval.max(1);
+  if interval.is_nan() || interval < 2.5 {
+    let interval = interval.round().max(1.0) as u32;
+    let interval = interval.min(maximum_interval);
+    return (interval, interval);
+  }
+  let delta = FUZZ_RANGES
+    .iter()
+    .fold(1.0, |delta, &(start, end, factor)| {
+      delta + factor * (interval.min(end) - start).max(0.0)
+    });
+  let max = ((interval + delta).round() as u32).min(maximum_interval);
+  let mut min = ((interval - delta).round() as u32).max(2);
+  if interval > elapsed_days as f64 {
+    min = min.max(elapsed_days + 1);
+  }
+  (min.min(max), max)
+}
+
+pub(crate) fn fuzzed_days(interval: f64, elapsed_days: u32, options: &FuzzOption) -> u32 {
+  let (min, max) = fuzz_bounds(
+    interval,
+    elapsed_days,
+    options.maximum_interval.unwrap_or(DEFAULT_MAXIMUM_INTERVAL),
+  );
+  let factor = fuzz_factor(options.card_id, options.review_count);
+  (min + (factor * (max - min + 1) as f64) as u32).min(max)
+}
+
+/// A number in `[0, 1)` derived from the card id and review count with SplitMix64, so the
+/// result doesn't depend on the platform or on the version of a random number crate.
+fn fuzz_factor(card_id: i64, review_count: u32) -> f64 {
+  let seed = splitmix64(card_id as u64) ^ splitmix64(review_count as u64);
+  (splitmix64(seed) >> 11) as f64 / (1u64 << 53) as f64
+}
+
+pub(crate) fn splitmix64(value: u64) -> u64 {
+  let mut z = value.wrapping_add(0x9e37_79b9_7f4a_7c15);
+  z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+  z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+  z ^ (z >> 31)
+}
+
+/// The day within the fuzz range of `interval` with the lowest `load`, preferring the
+/// day closest to `interval` and then the earlier one.
+pub(crate) fn load_balanced_days(
+  interval: f64,
+  elapsed_days: u32,
+  maximum_interval: u32,
+  load: impl Fn(u32) -> f64,
+) -> u32 {
+  let (min, max) = fuzz_bounds(interval, elapsed_days, maximum_interval);
+  let distance = |day: u32| (day as f64 - interval).abs();
+  (min..=max)
+    .min_by(|&a, &b| {
+      load(a)
+        .total_cmp(&load(b))
+        .then_with(|| distance(a).total_cmp(&distance(b)))
+    })
+    .unwrap_or(min)
+}
+
+/// Dividing by the easy days multiplier makes a reduced day count as busier, and a day
+/// with a multiplier of 0 as full.
+pub(crate) fn day_load(due_count: usize, multiplier: f64) -> f64 {
+  (due_count + 1) as f64 / multiplier
+}
+
+/// Load balances the intervals the simulator schedules with `easy_days`.
+pub(crate) fn easy_days_post_scheduling_fn(easy_days: EasyDays) -> fsrs::PostSchedulingFn {
+  fsrs::PostSchedulingFn::new(move |ctx| {
+    let due_counts = ctx.due_counts_per_day;
+    load_balanced_days(
+      ctx.card.interval as f64,
+      0,
+      ctx.max_interval as u32,
+      |day| {
+        let day = ctx.today + day as usize;
+        day_load(
+          due_counts.get(day).copied().unwrap_or(0),
+          easy_days.multiplier(day),
+        )
+      },
+    ) as f32
+  })
+}
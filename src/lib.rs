@@ -1,18 +1,45 @@
 #![deny(clippy::all)]
 #![allow(unexpected_cfgs)]
-use napi::JsNumber;
-use napi::bindgen_prelude::{AsyncTask, JsFunction, Result};
+use napi::bindgen_prelude::{
+  AsyncTask, Buffer, Either, Env, Float32Array, JsFunction, Result, Uint8Array, Uint32Array,
+};
+use napi::{JsNumber, JsObject};
 use std::sync::{Arc, Mutex};
 
+mod dataset;
+mod fuzz;
+mod optimal_retention;
+mod parameters;
+mod postpone;
+mod review_order;
+mod revlog;
+mod scheduler;
+mod simulator_hooks;
 mod train_task;
-use train_task::{ComputeParametersTask, ProgressData};
+use dataset::{Dataset, TrainSet, train_set_to_fsrs};
+use parameters::{ParameterInfo, Parameters};
+use postpone::{AdvanceOption, DueCard, DueChange, PostponeOption};
+use review_order::{ReviewOrder, ReviewOrderOption};
+use train_task::{
+  AbortListener, BenchmarkTask, ComputeParametersTask, EvaluateTask,
+  EvaluateWithTimeSeriesSplitsTask, OptimalRetentionTask, ProgressCallback, ProgressData,
+  SharedComputeParametersInput, SharedProgressState, SimulateTask,
+};
 
 // https://github.com/rust-lang/rust-analyzer/issues/17429
 use napi_derive::napi;
 
 #[napi(js_name = "FSRS")]
 #[derive(Debug)]
-pub struct FSRS(Arc<Mutex<fsrs::FSRS>>);
+pub struct FSRS {
+  /// Never mutated, so calls and background tasks share it without locking.
+  model: Arc<fsrs::FSRS>,
+  /// The filled parameters, which `fsrs::FSRS` does not expose. A model built from them
+  /// is the same as `model`.
+  parameters: Vec<f32>,
+  /// Decay of the forgetting curve, clipped like `fsrs::FSRS::new` does.
+  decay: f32,
+}
 #[napi]
 pub const FSRS5_DEFAULT_DECAY: f32 = 0.5;
 #[napi]
@@ -49,7 +76,26 @@ pub const DEFAULT_PARAMETERS: [f32; 21] = [
 
 impl Default for FSRS {
   fn default() -> Self {
-    Self(Arc::new(Mutex::new(fsrs::FSRS::default())))
+    Self {
+      model: Arc::new(fsrs::FSRS::default()),
+      parameters: DEFAULT_PARAMETERS.to_vec(),
+      decay: FSRS6_DEFAULT_DECAY,
+    }
+  }
+}
+
+impl FSRS {
+  fn from_parameters(parameters: &[f32]) -> Result<Self> {
+    let model = fsrs::FSRS::new(parameters)
+      .map_err(|e| napi::Error::from_reason(format!("FSRS initialization failed: {e}")))?;
+    let mut parameters = fsrs::check_and_fill_parameters(parameters)
+      .map_err(|e| fsrs_error("checkAndFillParameters", e))?;
+    parameters::clip_parameters(&mut parameters);
+    Ok(Self {
+      model: Arc::new(model),
+      decay: parameters[20],
+      parameters,
+    })
   }
 }
 
@@ -86,6 +132,13 @@ fn fsrs_error(action: &str, error: fsrs::FSRSError) -> napi::Error {
   napi_error(format!("FSRS {action} failed: {error}"))
 }
 
+fn validate_days_elapsed(days_elapsed: f64) -> Result<f32> {
+  if !days_elapsed.is_finite() || days_elapsed < 0.0 {
+    return Err(napi_error("daysElapsed must be a non-negative number"));
+  }
+  Ok(days_elapsed as f32)
+}
+
 fn validate_training_config(config: fsrs::TrainingConfig) -> Result<fsrs::TrainingConfig> {
   if config.batch_size == 0 {
     return Err(napi_error("batchSize must be greater than 0"));
@@ -208,21 +261,178 @@ fn training_config_from_options(
   Ok(Some(validate_training_config(config)?))
 }
 
-fn compute_parameters_input(
-  train_set: Vec<&FSRSItem>,
+// Turn `JsFunction` into a `ThreadsafeFunction`
+fn progress_callback_from_js(callback: Option<&JsFunction>) -> Result<Option<ProgressCallback>> {
+  callback
+    .map(|callback| {
+      callback.create_threadsafe_function(0, |ctx| {
+        let progress_data: ProgressData = ctx.value;
+        let env = ctx.env;
+        let current = env.create_uint32(progress_data.current as u32)?;
+        let total = env.create_uint32(progress_data.total as u32)?;
+        let percent = env.create_double(progress_data.percent)?;
+        let mut progress_obj = env.create_object()?;
+        progress_obj.set_named_property("current", current)?;
+        progress_obj.set_named_property("total", total)?;
+        progress_obj.set_named_property("percent", percent)?;
+        if let Some(epoch) = progress_data.epoch {
+          progress_obj
+            .set_named_property("splitIndex", env.create_uint32(epoch.split_index as u32)?)?;
+          progress_obj.set_named_property("epoch", env.create_uint32(epoch.epoch as u32)?)?;
+          progress_obj
+            .set_named_property("epochTotal", env.create_uint32(epoch.epoch_total as u32)?)?;
+          progress_obj.set_named_property(
+            "itemsProcessed",
+            env.create_uint32(epoch.items_processed as u32)?,
+          )?;
+          progress_obj
+            .set_named_property("itemsTotal", env.create_uint32(epoch.items_total as u32)?)?;
+        }
+        Ok(vec![progress_obj])
+      })
+    })
+    .transpose()
+}
+
+/// Creates the progress state a task shares with its `AbortSignal`, if any.
+/// Aborting sets `want_abort`, which the task checks between steps. The task removes
+/// the returned listener once it settles.
+fn progress_state_from_signal(
+  env: Env,
+  signal: Option<&JsObject>,
+) -> Result<(SharedProgressState, Option<AbortListener>)> {
+  let progress_state = fsrs::CombinedProgressState::new_shared();
+  let Some(signal) = signal else {
+    return Ok((progress_state, None));
+  };
+
+  if signal.get_named_property::<bool>("aborted")? {
+    progress_state.lock().unwrap().want_abort = true;
+    return Ok((progress_state, None));
+  }
+
+  let progress_state_for_listener = Arc::clone(&progress_state);
+  let on_abort = env
+    .create_function_from_closure("onabort", move |ctx| {
+      progress_state_for_listener.lock().unwrap().want_abort = true;
+      ctx.env.get_undefined()
+    })?
+    .into_unknown();
+  let mut listener_options = env.create_object()?;
+  listener_options.set_named_property("once", true)?;
+  let add_event_listener: JsFunction = signal.get_named_property("addEventListener")?;
+  add_event_listener.call(
+    Some(signal),
+    &[
+      &env.create_string("abort")?.into_unknown(),
+      &on_abort,
+      &listener_options.into_unknown(),
+    ],
+  )?;
+
+  let abort_listener = AbortListener {
+    signal: env.create_reference(signal)?,
+    listener: env.create_reference(on_abort)?,
+  };
+  Ok((progress_state, Some(abort_listener)))
+}
+
+fn compute_parameters_task(
+  env: Env,
+  train_data: Arc<Vec<fsrs::FSRSItem>>,
+  card_ids: Option<Vec<i64>>,
   options: Option<&ComputeParametersOption>,
-  progress: Option<Arc<Mutex<fsrs::CombinedProgressState>>>,
-) -> Result<fsrs::ComputeParametersInput> {
-  Ok(fsrs::ComputeParametersInput {
-    train_set: train_set.into_iter().map(|x| x.0.clone()).collect(),
-    card_ids: card_ids_from_options(options)?,
-    progress,
+) -> Result<AsyncTask<ComputeParametersTask>> {
+  let fn_form_js = progress_callback_from_js(options.and_then(|x| x.progress.as_ref()))?;
+  let (progress_state, abort_listener) =
+    progress_state_from_signal(env, options.and_then(|x| x.signal.as_ref()))?;
+
+  let task = ComputeParametersTask {
+    train_data,
+    card_ids: card_ids_from_options(options)?.or(card_ids),
     enable_short_term: enable_short_term_from_options(options),
     num_relearning_steps: num_relearning_steps_from_options(options),
     training_config: training_config_from_options(options)?,
+    progress_callback: fn_form_js,
+    progress_timeout: progress_timeout_from_options(options),
+    progress_state,
+    abort_listener,
+  };
+
+  Ok(AsyncTask::new(task))
+}
+
+fn items_from_arrays(
+  ratings: &[u32],
+  delta_ts: &[u32],
+  item_offsets: &[u32],
+) -> Result<Vec<fsrs::FSRSItem>> {
+  if ratings.len() != delta_ts.len() {
+    return Err(napi_error("ratings and deltaTs must have the same length"));
+  }
+  if item_offsets.first().is_some_and(|&offset| offset != 0)
+    || item_offsets.last().map_or(0, |&offset| offset as usize) != ratings.len()
+  {
+    return Err(napi_error(
+      "itemOffsets must start at 0 and end at the number of reviews",
+    ));
+  }
+  if item_offsets.windows(2).any(|window| window[0] >= window[1]) {
+    return Err(napi_error(
+      "itemOffsets must be increasing, so that every item has reviews",
+    ));
+  }
+
+  Ok(
+    item_offsets
+      .windows(2)
+      .map(|window| {
+        let (start, end) = (window[0] as usize, window[1] as usize);
+        fsrs::FSRSItem {
+          reviews: ratings[start..end]
+            .iter()
+            .zip(&delta_ts[start..end])
+            .map(|(&rating, &delta_t)| fsrs::FSRSReview { rating, delta_t })
+            .collect(),
+        }
+      })
+      .collect(),
+  )
+}
+
+/// `cardIds` from the options take precedence over the ones stored in a `Dataset`.
+fn compute_parameters_input(
+  train_set: TrainSet,
+  options: Option<&ComputeParametersOption>,
+  progress: Option<Arc<Mutex<fsrs::CombinedProgressState>>>,
+) -> Result<SharedComputeParametersInput> {
+  let (train_set, card_ids) = train_set_to_fsrs(train_set);
+  Ok(SharedComputeParametersInput {
+    train_set,
+    input: fsrs::ComputeParametersInput {
+      train_set: Vec::new(),
+      card_ids: card_ids_from_options(options)?.or(card_ids),
+      progress,
+      enable_short_term: enable_short_term_from_options(options),
+      num_relearning_steps: num_relearning_steps_from_options(options),
+      training_config: training_config_from_options(options)?,
+    },
   })
 }
 
+/// With `card_ids`, the items of each card are evaluated together, like in training.
+fn evaluate_model(
+  model: &fsrs::FSRS,
+  items: Vec<fsrs::FSRSItem>,
+  card_ids: Option<Vec<i64>>,
+  progress: impl FnMut(fsrs::ItemProgress) -> bool,
+) -> std::result::Result<fsrs::ModelEvaluation, fsrs::FSRSError> {
+  match card_ids {
+    Some(card_ids) => model.evaluate_with_card_ids(items, card_ids, progress),
+    None => model.evaluate(items, progress),
+  }
+}
+
 fn convert_starting_states(
   starting_states: Option<Vec<Option<&MemoryState>>>,
   len: usize,
@@ -245,118 +455,407 @@ impl FSRS {
   #[napi(constructor, catch_unwind)]
   pub fn new(parameters: Option<Vec<JsNumber>>) -> Result<Self> {
     let params = js_numbers_to_f32(parameters.unwrap_or_default())?;
-    let model = fsrs::FSRS::new(&params)
-      .map_err(|e| napi::Error::from_reason(format!("FSRS initialization failed: {e}")))?;
-    Ok(Self(Arc::new(Mutex::new(model))))
+    Self::from_parameters(&params)
+  }
+
+  /// The 21 parameters of the model, with defaults filled in for an empty array, older
+  /// 17 or 19 parameter sets converted, and each clipped to its {@link parameterInfo}
+  /// bounds like the model does.
+  #[napi(getter)]
+  pub fn parameters(&self) -> Vec<f64> {
+    self
+      .parameters
+      .iter()
+      .map(|&parameter| parameter as f64)
+      .collect()
+  }
+
+  /// Replace the parameters, checked like in [`new FSRS()`]{@link constructor}. If they
+  /// are invalid, the model is left unchanged. Running background tasks keep using the
+  /// previous parameters.
+  #[napi]
+  pub fn set_parameters(&mut self, parameters: Vec<JsNumber>) -> Result<()> {
+    *self = Self::from_parameters(&js_numbers_to_f32(parameters)?)?;
+    Ok(())
+  }
+
+  /// The model as a compact buffer of 21 little-endian 32-bit floats, e.g. to post to a
+  /// `worker_threads` worker, which rebuilds it with {@link fromBuffer}.
+  #[napi]
+  pub fn to_buffer(&self) -> Buffer {
+    self
+      .parameters
+      .iter()
+      .flat_map(|parameter| parameter.to_le_bytes())
+      .collect::<Vec<_>>()
+      .into()
+  }
+
+  /// Rebuild a model exported with {@link toBuffer}.
+  #[napi(factory)]
+  pub fn from_buffer(buffer: Uint8Array) -> Result<Self> {
+    if buffer.len() != 21 * 4 {
+      return Err(napi_error("buffer must hold 21 parameters of 4 bytes each"));
+    }
+    let parameters = buffer
+      .chunks_exact(4)
+      .map(|bytes| f32::from_le_bytes(bytes.try_into().unwrap()))
+      .collect::<Vec<_>>();
+    Self::from_parameters(&parameters)
   }
 
   /// Calculate appropriate parameters for the provided review history.
   #[napi(ts_return_type = "Promise<Array<number>>")]
   pub fn compute_parameters(
     &self,
-    train_set: Vec<&FSRSItem>,
+    env: Env,
+    #[napi(ts_arg_type = "Array<FSRSItem> | Dataset")] train_set: TrainSet,
     #[napi(ts_arg_type = "ComputeParametersOption")] options: Option<ComputeParametersOption>,
   ) -> Result<AsyncTask<ComputeParametersTask>> {
     // Convert your `JS` training items to owned `fsrs::FSRSItem`
-    let train_data = train_set
-      .into_iter()
-      .map(|item| item.0.clone())
-      .collect::<Vec<_>>();
-
-    // Turn `JsFunction` into a `ThreadsafeFunction`
-    let fn_form_js = if let Some(callback) = options.as_ref().and_then(|x| x.progress.as_ref()) {
-      Some(callback.create_threadsafe_function(0, |ctx| {
-        let progress_data: ProgressData = ctx.value;
-        let env = ctx.env;
-        let current = env.create_uint32(progress_data.current as u32)?;
-        let total = env.create_uint32(progress_data.total as u32)?;
-        let percent = env.create_double(progress_data.percent)?;
-        let mut progress_obj = env.create_object()?;
-        progress_obj.set_named_property("current", current)?;
-        progress_obj.set_named_property("total", total)?;
-        progress_obj.set_named_property("percent", percent)?;
-        Ok(vec![progress_obj])
-      })?)
-    } else {
-      None
-    };
+    let (train_data, card_ids) = train_set_to_fsrs(train_set);
 
-    let task = ComputeParametersTask {
-      train_data,
-      card_ids: card_ids_from_options(options.as_ref())?,
-      enable_short_term: enable_short_term_from_options(options.as_ref()),
-      num_relearning_steps: num_relearning_steps_from_options(options.as_ref()),
-      training_config: training_config_from_options(options.as_ref())?,
-      progress_callback: fn_form_js,
-      progress_timeout: progress_timeout_from_options(options.as_ref()),
-    };
+    compute_parameters_task(env, train_data, card_ids, options.as_ref())
+  }
 
-    Ok(AsyncTask::new(task))
+  /// Like {@link computeParameters}, but takes the review history as flat typed arrays,
+  /// which avoids creating a JS object per review for large collections.
+  ///
+  /// Item `i` consists of the reviews from `itemOffsets[i]` up to (excluding)
+  /// `itemOffsets[i + 1]`, so `itemOffsets` starts with 0, ends with the number of
+  /// reviews, increases strictly and has one more entry than there are items.
+  #[napi(ts_return_type = "Promise<Array<number>>")]
+  pub fn compute_parameters_from_arrays(
+    &self,
+    env: Env,
+    ratings: Uint32Array,
+    delta_ts: Uint32Array,
+    item_offsets: Uint32Array,
+    #[napi(ts_arg_type = "ComputeParametersOption")] options: Option<ComputeParametersOption>,
+  ) -> Result<AsyncTask<ComputeParametersTask>> {
+    let train_data = items_from_arrays(&ratings, &delta_ts, &item_offsets)?;
+    compute_parameters_task(env, Arc::new(train_data), None, options.as_ref())
   }
 
   /// The intervals and memory states for each answer button.
   ///
   /// Parameters must have been provided when calling [`new FSRS()`]{@link constructor}.
+  ///
+  /// With `fuzz`, the intervals are rounded to whole days and spread with {@link fuzzInterval}.
+  /// With `loadBalance`, they are instead moved to the least busy day with
+  /// {@link loadBalanceInterval}, which already picks a day within the fuzz range, so
+  /// `fuzz` and `loadBalance` can't be combined.
   #[napi]
   pub fn next_states(
     &self,
     current_memory_state: Option<&MemoryState>,
     desired_retention: f64,
     days_elapsed: u32,
+    fuzz: Option<FuzzOption>,
+    load_balance: Option<LoadBalanceOption>,
   ) -> Result<NextStates> {
-    let locked_model = self.0.lock().unwrap();
-    Ok(NextStates(
-      locked_model
-        .next_states(
-          current_memory_state.map(|x| x.0),
-          desired_retention as f32,
+    if fuzz.is_some() && load_balance.is_some() {
+      return Err(napi_error("fuzz and loadBalance can't be combined"));
+    }
+    if let Some(easy_days) = load_balance.as_ref().and_then(|x| x.easy_days.as_ref()) {
+      easy_days.validate()?;
+    }
+    let mut next_states = self
+      .model
+      .next_states(
+        current_memory_state.map(|x| x.0),
+        desired_retention as f32,
+        days_elapsed,
+      )
+      .map_err(|e| fsrs_error("nextStates", e))?;
+    for state in [
+      &mut next_states.again,
+      &mut next_states.hard,
+      &mut next_states.good,
+      &mut next_states.easy,
+    ] {
+      let interval = state.interval as f64;
+      if let Some(load_balance) = &load_balance {
+        state.interval = load_balanced_days(
+          interval,
           days_elapsed,
-        )
-        .map_err(|e| fsrs_error("nextStates", e))?,
-    ))
+          &load_balance.due_counts,
+          load_balance.maximum_interval,
+          load_balance.easy_days.as_ref(),
+        ) as f32;
+      } else if let Some(fuzz) = &fuzz {
+        state.interval = fuzz::fuzzed_days(interval, days_elapsed, fuzz) as f32;
+      }
+    }
+    Ok(NextStates(next_states))
+  }
+
+  /// Like {@link nextStates} for many cards at once, without creating objects per card.
+  ///
+  /// Cards with a stability of 0 are new, the difficulty of others must be between 1 and
+  /// 10. Fractions of `elapsedDays` are dropped. The results for card `i` are at `4 * i`
+  /// to `4 * i + 3`, for Again, Hard, Good and Easy.
+  #[napi]
+  pub fn next_states_batch(
+    &self,
+    stabilities: Float32Array,
+    difficulties: Float32Array,
+    elapsed_days: Float32Array,
+    desired_retention: f64,
+  ) -> Result<NextStatesBatch> {
+    let count = stabilities.len();
+    if difficulties.len() != count || elapsed_days.len() != count {
+      return Err(napi_error(
+        "stabilities, difficulties and elapsedDays must have the same length",
+      ));
+    }
+    let mut intervals = Vec::with_capacity(count * 4);
+    let mut next_stabilities = Vec::with_capacity(count * 4);
+    let mut next_difficulties = Vec::with_capacity(count * 4);
+    for i in 0..count {
+      if !stabilities[i].is_finite() || stabilities[i] < 0.0 {
+        return Err(napi_error(format!(
+          "stabilities[{i}] must be 0 or a positive number"
+        )));
+      }
+      if stabilities[i] != 0.0 && !(1.0..=10.0).contains(&difficulties[i]) {
+        return Err(napi_error(format!(
+          "difficulties[{i}] must be between 1 and 10"
+        )));
+      }
+      let memory_state = (stabilities[i] != 0.0).then(|| fsrs::MemoryState {
+        stability: stabilities[i],
+        difficulty: difficulties[i],
+      });
+      let days_elapsed = validate_days_elapsed(elapsed_days[i] as f64)? as u32;
+      let next_states = self
+        .model
+        .next_states(memory_state, desired_retention as f32, days_elapsed)
+        .map_err(|e| fsrs_error("nextStatesBatch", e))?;
+      for state in [
+        next_states.again,
+        next_states.hard,
+        next_states.good,
+        next_states.easy,
+      ] {
+        intervals.push(state.interval);
+        next_stabilities.push(state.memory.stability);
+        next_difficulties.push(state.memory.difficulty);
+      }
+    }
+    Ok(NextStatesBatch {
+      intervals: Float32Array::new(intervals),
+      stabilities: Float32Array::new(next_stabilities),
+      difficulties: Float32Array::new(next_difficulties),
+    })
   }
 
+  /// With `fuzz`, the interval is rounded to whole days and spread with {@link fuzzInterval}.
   #[napi]
-  pub fn next_interval(&self, stability: Option<f64>, desired_retention: f64, rating: u32) -> f32 {
-    let locked_model = self.0.lock().unwrap();
-    locked_model.next_interval(
+  pub fn next_interval(
+    &self,
+    stability: Option<f64>,
+    desired_retention: f64,
+    rating: u32,
+    fuzz: Option<FuzzOption>,
+  ) -> f32 {
+    let interval = self.model.next_interval(
       stability.map(|value| value as f32),
       desired_retention as f32,
       rating,
-    )
+    );
+    match fuzz {
+      Some(fuzz) => fuzz::fuzzed_days(interval as f64, 0, &fuzz) as f32,
+      None => interval,
+    }
   }
 
   #[napi]
   pub fn benchmark(
     &self,
-    train_set: Vec<&FSRSItem>,
+    #[napi(ts_arg_type = "Array<FSRSItem> | Dataset")] train_set: TrainSet,
     #[napi(ts_arg_type = "ComputeParametersOption")] options: Option<ComputeParametersOption>,
   ) -> Result<Vec<f32>> {
-    Ok(fsrs::benchmark(compute_parameters_input(
-      train_set,
-      options.as_ref(),
-      None,
-    )?))
+    Ok(fsrs::benchmark(
+      compute_parameters_input(train_set, options.as_ref(), None)?.into_fsrs(),
+    ))
+  }
+
+  /// Like {@link benchmark}, but runs on a background thread and resolves with the parameters.
+  #[napi(ts_return_type = "Promise<Array<number>>")]
+  pub fn benchmark_async(
+    &self,
+    #[napi(ts_arg_type = "Array<FSRSItem> | Dataset")] train_set: TrainSet,
+    #[napi(ts_arg_type = "ComputeParametersOption")] options: Option<ComputeParametersOption>,
+  ) -> Result<AsyncTask<BenchmarkTask>> {
+    Ok(AsyncTask::new(BenchmarkTask {
+      input: compute_parameters_input(train_set, options.as_ref(), None)?,
+    }))
   }
 
-  /// Determine how well the model and parameters predict performance.
+  /// Determine how well the model and parameters predict performance. The items of a
+  /// {@link Dataset} with `cardIds` are evaluated per card, like in training.
   ///
   /// Parameters must have been provided when calling [`new FSRS()`]{@link constructor}.
   #[napi]
-  pub fn evaluate(&self, train_set: Vec<&FSRSItem>) -> Result<ModelEvaluation> {
+  pub fn evaluate(
+    &self,
+    #[napi(ts_arg_type = "Array<FSRSItem> | Dataset")] train_set: TrainSet,
+  ) -> Result<ModelEvaluation> {
     // Convert your `JS` training items to owned `fsrs::FSRSItem`
-    let train_data = train_set
-      .into_iter()
-      .map(|item| item.0.clone())
-      .collect::<Vec<_>>();
+    let (train_data, card_ids) = train_set_to_fsrs(train_set);
 
-    let locked_model = self.0.lock().unwrap();
-    let result = locked_model
-      .evaluate(train_data, |_| true)
-      .map_err(|e| napi::Error::from_reason(format!("FSRS evaluate failed: {e}")))?;
+    let result = evaluate_model(
+      &self.model,
+      Arc::unwrap_or_clone(train_data),
+      card_ids,
+      |_| true,
+    )
+    .map_err(|e| napi::Error::from_reason(format!("FSRS evaluate failed: {e}")))?;
     Ok(result.into())
   }
 
+  /// Like {@link evaluate}, but runs on a background thread so the event loop stays responsive.
+  ///
+  /// Parameters must have been provided when calling [`new FSRS()`]{@link constructor}.
+  #[napi(ts_return_type = "Promise<ModelEvaluation>")]
+  pub fn evaluate_async(
+    &self,
+    #[napi(ts_arg_type = "Array<FSRSItem> | Dataset")] train_set: TrainSet,
+    #[napi(ts_arg_type = "EvaluateOption")] options: Option<EvaluateOption>,
+  ) -> Result<AsyncTask<EvaluateTask>> {
+    let (train_data, card_ids) = train_set_to_fsrs(train_set);
+    let model = Arc::clone(&self.model);
+
+    Ok(AsyncTask::new(EvaluateTask {
+      model,
+      train_data,
+      card_ids,
+      progress_callback: progress_callback_from_js(
+        options.as_ref().and_then(|x| x.progress.as_ref()),
+      )?,
+    }))
+  }
+
+  /// The probability of recalling a card `daysElapsed` days after its last review,
+  /// according to the forgetting curve of this model's decay.
+  #[napi]
+  pub fn current_retrievability(
+    &self,
+    memory_state: &MemoryState,
+    days_elapsed: f64,
+  ) -> Result<f64> {
+    let days_elapsed = validate_days_elapsed(days_elapsed)?;
+    Ok(fsrs::current_retrievability(memory_state.0, days_elapsed, self.decay) as f64)
+  }
+
+  /// Sample the forgetting curve of a memory state at each of the given day offsets,
+  /// as {@link currentRetrievability} would for each of them.
+  #[napi]
+  pub fn forgetting_curve(
+    &self,
+    memory_state: &MemoryState,
+    days_elapsed: Vec<f64>,
+  ) -> Result<Vec<f64>> {
+    days_elapsed
+      .into_iter()
+      .map(|days_elapsed| {
+        let days_elapsed = validate_days_elapsed(days_elapsed)?;
+        Ok(fsrs::current_retrievability(memory_state.0, days_elapsed, self.decay) as f64)
+      })
+      .collect()
+  }
+
+  /// Select cards due by `today` to postpone, those losing the least retrievability
+  /// first. Each card is postponed by 5% of its interval, and at least a day.
+  #[napi]
+  pub fn postpone_cards(
+    &self,
+    cards: Vec<DueCard>,
+    today: i64,
+    options: Option<PostponeOption>,
+  ) -> Result<Vec<DueChange>> {
+    postpone::postpone(&cards, today, options, self.decay)
+  }
+
+  /// Select cards that are not due yet to review `today` instead, e.g. before a trip,
+  /// those with the lowest retrievability first, as reviewing them early wastes the least.
+  #[napi]
+  pub fn advance_cards(
+    &self,
+    cards: Vec<DueCard>,
+    today: i64,
+    options: Option<AdvanceOption>,
+  ) -> Result<Vec<DueChange>> {
+    postpone::advance(&cards, today, options, self.decay)
+  }
+
+  /// The order in which to review cards, as indices into the arrays, which hold the
+  /// memory state of each card and the days since its last review.
+  #[napi]
+  pub fn sort_cards(
+    &self,
+    stabilities: Float32Array,
+    difficulties: Float32Array,
+    elapsed_days: Float32Array,
+    order: ReviewOrder,
+    options: Option<ReviewOrderOption>,
+  ) -> Result<Uint32Array> {
+    let count = stabilities.len();
+    if difficulties.len() != count || elapsed_days.len() != count {
+      return Err(napi_error(
+        "stabilities, difficulties and elapsedDays must have the same length",
+      ));
+    }
+    if stabilities
+      .iter()
+      .any(|&stability| stability.is_nan() || stability <= 0.0)
+    {
+      return Err(napi_error("stabilities must be positive"));
+    }
+    for &days_elapsed in elapsed_days.iter() {
+      validate_days_elapsed(days_elapsed as f64)?;
+    }
+    let desired_retention = options
+      .as_ref()
+      .and_then(|x| x.desired_retention)
+      .unwrap_or(0.9);
+    if !(desired_retention > 0.0 && desired_retention < 1.0) {
+      return Err(napi_error("desiredRetention must be between 0 and 1"));
+    }
+    let seed = options.and_then(|x| x.seed).unwrap_or_default();
+
+    let retrievability = |i: usize| {
+      let state = fsrs::MemoryState {
+        stability: stabilities[i],
+        difficulty: difficulties[i],
+      };
+      fsrs::current_retrievability(state, elapsed_days[i], self.decay) as f64
+    };
+    let indices = match order {
+      ReviewOrder::RetrievabilityAscending => review_order::sort_by_key(count, retrievability),
+      ReviewOrder::RetrievabilityDescending => {
+        review_order::sort_by_key(count, |i| -retrievability(i))
+      }
+      ReviewOrder::RelativeOverdueness => review_order::sort_by_key(count, |i| {
+        let interval = self
+          .model
+          .next_interval(Some(stabilities[i]), desired_retention as f32, 1);
+        -(elapsed_days[i] / interval) as f64
+      }),
+      ReviewOrder::DifficultyAscending => {
+        review_order::sort_by_key(count, |i| difficulties[i] as f64)
+      }
+      ReviewOrder::DifficultyDescending => {
+        review_order::sort_by_key(count, |i| -difficulties[i] as f64)
+      }
+      ReviewOrder::Random => {
+        review_order::sort_by_key(count, |i| review_order::random_key(seed, i))
+      }
+    };
+    Ok(Uint32Array::new(indices))
+  }
+
   /// If a card has incomplete learning history, memory state can be approximated from
   /// current sm2 values.
   ///
@@ -368,9 +867,9 @@ impl FSRS {
     interval: f64,
     sm2_retention: f64,
   ) -> Result<MemoryState> {
-    let locked_model = self.0.lock().unwrap();
     Ok(MemoryState(
-      locked_model
+      self
+        .model
         .memory_state_from_sm2(ease_factor as f32, interval as f32, sm2_retention as f32)
         .map_err(|e| fsrs_error("memoryStateFromSm2", e))?,
     ))
@@ -388,9 +887,9 @@ impl FSRS {
     item: &FSRSItem,
     starting_state: Option<&MemoryState>,
   ) -> Result<MemoryState> {
-    let locked_model = self.0.lock().unwrap();
     Ok(MemoryState(
-      locked_model
+      self
+        .model
         .memory_state(item.0.clone(), starting_state.map(|x| x.0))
         .map_err(|e| fsrs_error("memoryState", e))?,
     ))
@@ -405,8 +904,8 @@ impl FSRS {
     >,
   ) -> Result<Vec<MemoryState>> {
     let starting_states = convert_starting_states(starting_states, items.len());
-    let locked_model = self.0.lock().unwrap();
-    locked_model
+    self
+      .model
       .memory_state_batch(
         items.into_iter().map(|item| item.0.clone()).collect(),
         starting_states,
@@ -421,8 +920,8 @@ impl FSRS {
     item: &FSRSItem,
     starting_state: Option<&MemoryState>,
   ) -> Result<Vec<MemoryState>> {
-    let locked_model = self.0.lock().unwrap();
-    locked_model
+    self
+      .model
       .historical_memory_states(item.0.clone(), starting_state.map(|x| x.0))
       .map(|states| states.into_iter().map(MemoryState).collect())
       .map_err(|e| fsrs_error("historicalMemoryStates", e))
@@ -436,8 +935,8 @@ impl FSRS {
       Vec<Option<&MemoryState>>,
     >,
   ) -> Result<Vec<Vec<MemoryState>>> {
-    let locked_model = self.0.lock().unwrap();
-    locked_model
+    self
+      .model
       .historical_memory_state_batch(
         items.into_iter().map(|item| item.0.clone()).collect(),
         starting_states.map(|states| {
@@ -480,8 +979,14 @@ impl FSRSReview {
     self.0.delta_t
   }
   #[napi(js_name = "toJSON")]
-  pub fn to_json(&self) -> String {
-    format!("{:?}", self.0)
+  pub fn to_json(&self) -> FSRSReviewJson {
+    FSRSReviewJson::from(&self.0)
+  }
+
+  /// Restore a value returned by {@link toJSON}, e.g. after `JSON.parse`.
+  #[napi(js_name = "fromJSON")]
+  pub fn from_json(json: FSRSReviewJson) -> Self {
+    Self(json.into())
   }
 }
 
@@ -518,9 +1023,77 @@ impl FSRSItem {
     self.0.long_term_review_cnt() as u32
   }
 
+  /// Read an Anki-style revlog CSV (a file path or its contents) and convert it into
+  /// items sorted by review time, ready for {@link FSRS.computeParameters}.
+  ///
+  /// The CSV needs `card_id`, `review_time` (unix ms) and `review_rating` columns.
+  /// If it has a `review_state` column, reviews before each card's last learning block
+  /// are dropped. Reviews with a rating outside 1-4 are ignored.
+  #[napi]
+  pub fn from_revlog_csv(
+    #[napi(ts_arg_type = "string | Buffer")] source: Either<String, Buffer>,
+    options: Option<DayCutoffOption>,
+  ) -> Result<RevlogImportResult> {
+    let content = match source {
+      Either::A(path) => std::fs::read_to_string(&path)
+        .map_err(|e| napi_error(format!("Failed to read revlog CSV {path}: {e}")))?,
+      Either::B(buffer) => String::from_utf8(buffer.to_vec())
+        .map_err(|_| napi_error("revlog CSV must be valid UTF-8"))?,
+    };
+    let cutoff = revlog::DayCutoff::new(
+      options.as_ref().and_then(|x| x.next_day_starts_at),
+      options.as_ref().and_then(|x| x.utc_offset_minutes),
+    )?;
+    let (items, card_ids) = revlog::revlog_to_items(revlog::parse_revlog_csv(&content)?, cutoff);
+    Ok(RevlogImportResult {
+      items: Arc::new(items),
+      card_ids,
+    })
+  }
+
+  /// Convert timestamped reviews of any number of cards into items sorted by review time.
+  ///
+  /// Reviews of a card on the same day get a `deltaT` of 0, and an item is only produced
+  /// for a review on a new day, so every item has a {@link longTermReviewCnt} of at least 1.
+  #[napi]
+  pub fn from_review_events(
+    events: Vec<ReviewEvent>,
+    options: Option<DayCutoffOption>,
+  ) -> Result<RevlogImportResult> {
+    let cutoff = revlog::DayCutoff::new(
+      options.as_ref().and_then(|x| x.next_day_starts_at),
+      options.as_ref().and_then(|x| x.utc_offset_minutes),
+    )?;
+    let entries = events
+      .into_iter()
+      .map(|event| {
+        if !(1..=4).contains(&event.rating) {
+          return Err(napi_error("ReviewEvent rating must be between 1 and 4"));
+        }
+        Ok(revlog::RevlogEntry {
+          card_id: event.card_id,
+          review_time: event.timestamp_ms,
+          rating: event.rating,
+          state: None,
+        })
+      })
+      .collect::<Result<Vec<_>>>()?;
+    let (items, card_ids) = revlog::revlog_to_items(entries, cutoff);
+    Ok(RevlogImportResult {
+      items: Arc::new(items),
+      card_ids,
+    })
+  }
+
   #[napi(js_name = "toJSON")]
-  pub fn to_json(&self) -> String {
-    format!("{:?}", self.0)
+  pub fn to_json(&self) -> FSRSItemJson {
+    FSRSItemJson::from(&self.0)
+  }
+
+  /// Restore a value returned by {@link toJSON}, e.g. after `JSON.parse`.
+  #[napi(js_name = "fromJSON")]
+  pub fn from_json(json: FSRSItemJson) -> Self {
+    Self(json.into())
   }
 }
 
@@ -545,8 +1118,14 @@ impl MemoryState {
     self.0.difficulty as f64
   }
   #[napi(js_name = "toJSON")]
-  pub fn to_json(&self) -> String {
-    format!("{:?}", self.0)
+  pub fn to_json(&self) -> MemoryStateJson {
+    MemoryStateJson::from(&self.0)
+  }
+
+  /// Restore a value returned by {@link toJSON}, e.g. after `JSON.parse`.
+  #[napi(js_name = "fromJSON")]
+  pub fn from_json(json: MemoryStateJson) -> Self {
+    Self(json.into())
   }
 }
 
@@ -572,8 +1151,14 @@ impl NextStates {
     ItemState(self.0.again.clone())
   }
   #[napi(js_name = "toJSON")]
-  pub fn to_json(&self) -> String {
-    format!("{:?}", self.0)
+  pub fn to_json(&self) -> NextStatesJson {
+    NextStatesJson::from(&self.0)
+  }
+
+  /// Restore a value returned by {@link toJSON}, e.g. after `JSON.parse`.
+  #[napi(js_name = "fromJSON")]
+  pub fn from_json(json: NextStatesJson) -> Self {
+    Self(json.into())
   }
 }
 
@@ -591,8 +1176,150 @@ impl ItemState {
     self.0.interval
   }
   #[napi(js_name = "toJSON")]
-  pub fn to_json(&self) -> String {
-    format!("{:?}", self.0)
+  pub fn to_json(&self) -> ItemStateJson {
+    ItemStateJson::from(&self.0)
+  }
+
+  /// Restore a value returned by {@link toJSON}, e.g. after `JSON.parse`.
+  #[napi(js_name = "fromJSON")]
+  pub fn from_json(json: ItemStateJson) -> Self {
+    Self(json.into())
+  }
+}
+
+/// Plain-object form of {@link FSRSReview}.
+#[napi(object, js_name = "FSRSReviewJson")]
+pub struct FSRSReviewJson {
+  pub rating: u32,
+  pub delta_t: u32,
+}
+
+impl From<&fsrs::FSRSReview> for FSRSReviewJson {
+  fn from(review: &fsrs::FSRSReview) -> Self {
+    Self {
+      rating: review.rating,
+      delta_t: review.delta_t,
+    }
+  }
+}
+
+impl From<FSRSReviewJson> for fsrs::FSRSReview {
+  fn from(json: FSRSReviewJson) -> Self {
+    Self {
+      rating: json.rating,
+      delta_t: json.delta_t,
+    }
+  }
+}
+
+/// Plain-object form of {@link FSRSItem}.
+#[napi(object, js_name = "FSRSItemJson")]
+pub struct FSRSItemJson {
+  pub reviews: Vec<FSRSReviewJson>,
+}
+
+impl From<&fsrs::FSRSItem> for FSRSItemJson {
+  fn from(item: &fsrs::FSRSItem) -> Self {
+    Self {
+      reviews: item.reviews.iter().map(FSRSReviewJson::from).collect(),
+    }
+  }
+}
+
+impl From<FSRSItemJson> for fsrs::FSRSItem {
+  fn from(json: FSRSItemJson) -> Self {
+    Self {
+      reviews: json.reviews.into_iter().map(Into::into).collect(),
+    }
+  }
+}
+
+/// The result of {@link FSRS.nextStatesBatch}, four entries per card.
+#[napi(object)]
+pub struct NextStatesBatch {
+  pub intervals: Float32Array,
+  pub stabilities: Float32Array,
+  pub difficulties: Float32Array,
+}
+
+/// Plain-object form of {@link MemoryState}.
+#[napi(object)]
+pub struct MemoryStateJson {
+  pub stability: f64,
+  pub difficulty: f64,
+}
+
+impl From<&fsrs::MemoryState> for MemoryStateJson {
+  fn from(state: &fsrs::MemoryState) -> Self {
+    Self {
+      stability: state.stability as f64,
+      difficulty: state.difficulty as f64,
+    }
+  }
+}
+
+impl From<MemoryStateJson> for fsrs::MemoryState {
+  fn from(json: MemoryStateJson) -> Self {
+    Self {
+      stability: json.stability as f32,
+      difficulty: json.difficulty as f32,
+    }
+  }
+}
+
+/// Plain-object form of {@link ItemState}.
+#[napi(object)]
+pub struct ItemStateJson {
+  pub memory: MemoryStateJson,
+  pub interval: f64,
+}
+
+impl From<&fsrs::ItemState> for ItemStateJson {
+  fn from(state: &fsrs::ItemState) -> Self {
+    Self {
+      memory: MemoryStateJson::from(&state.memory),
+      interval: state.interval as f64,
+    }
+  }
+}
+
+impl From<ItemStateJson> for fsrs::ItemState {
+  fn from(json: ItemStateJson) -> Self {
+    Self {
+      memory: json.memory.into(),
+      interval: json.interval as f32,
+    }
+  }
+}
+
+/// Plain-object form of {@link NextStates}.
+#[napi(object)]
+pub struct NextStatesJson {
+  pub again: ItemStateJson,
+  pub hard: ItemStateJson,
+  pub good: ItemStateJson,
+  pub easy: ItemStateJson,
+}
+
+impl From<&fsrs::NextStates> for NextStatesJson {
+  fn from(states: &fsrs::NextStates) -> Self {
+    Self {
+      again: ItemStateJson::from(&states.again),
+      hard: ItemStateJson::from(&states.hard),
+      good: ItemStateJson::from(&states.good),
+      easy: ItemStateJson::from(&states.easy),
+    }
+  }
+}
+
+impl From<NextStatesJson> for fsrs::NextStates {
+  fn from(json: NextStatesJson) -> Self {
+    Self {
+      again: json.again.into(),
+      hard: json.hard.into(),
+      good: json.good.into(),
+      easy: json.easy.into(),
+    }
   }
 }
 
@@ -651,6 +1378,53 @@ impl From<fsrs::SimulationResult> for SimulationResult {
   }
 }
 
+/// Totals of a simulation, for comparing scheduling settings.
+#[napi(object)]
+pub struct SimulationSummary {
+  /// Seconds spent over the whole simulation.
+  pub total_cost: f64,
+  pub review_count: u32,
+  /// The share of reviews answered correctly.
+  pub true_retention: f64,
+  /// How many cards are remembered at the end of the simulation.
+  pub memorized: f64,
+  /// Reviews on each day of the week, Monday first.
+  pub reviews_per_weekday: Vec<u32>,
+}
+
+impl SimulationSummary {
+  /// `weekday` is the day of the week of the first simulated day, 0 for Monday.
+  fn new(result: &fsrs::SimulationResult, weekday: u32) -> Self {
+    let review_count = result.review_cnt_per_day.iter().sum::<usize>();
+    let correct_count = result.correct_cnt_per_day.iter().sum::<usize>();
+    let mut reviews_per_weekday = vec![0; 7];
+    for (day, &count) in result.review_cnt_per_day.iter().enumerate() {
+      reviews_per_weekday[(weekday as usize + day) % 7] += count as u32;
+    }
+    Self {
+      total_cost: result.cost_per_day.iter().map(|&cost| cost as f64).sum(),
+      review_count: review_count as u32,
+      true_retention: if review_count == 0 {
+        0.0
+      } else {
+        correct_count as f64 / review_count as f64
+      },
+      memorized: result
+        .memorized_cnt_per_day
+        .last()
+        .copied()
+        .unwrap_or_default() as f64,
+      reviews_per_weekday,
+    }
+  }
+}
+
+#[napi(object)]
+pub struct EasyDaysComparison {
+  pub baseline: SimulationSummary,
+  pub easy_days: SimulationSummary,
+}
+
 #[napi(object)]
 pub struct SimulatorConfig {
   pub deck_size: u32,
@@ -668,10 +1442,29 @@ pub struct SimulatorConfig {
   pub learning_step_count: u32,
   pub relearning_step_count: u32,
   pub suspend_after_lapses: Option<u32>,
+  /// Replaces the interval the simulator scheduled for a reviewed card, e.g. to apply
+  /// fuzz or load balancing. Only supported by `simulateAsync` and `computeOptimalRetention`.
+  #[napi(ts_type = "(context: PostSchedulingContext) => number")]
+  pub post_scheduling_fn: Option<JsFunction>,
+  /// The order in which due cards are reviewed, lower values first. Only supported by
+  /// `simulateAsync` and `computeOptimalRetention`.
+  #[napi(ts_type = "(card: SimulatorCard) => number")]
+  pub review_priority_fn: Option<JsFunction>,
+  /// Load balance the scheduled intervals away from easy days. Can't be combined with
+  /// `postSchedulingFn`.
+  pub easy_days: Option<EasyDays>,
 }
 
 impl SimulatorConfig {
   fn into_fsrs(self) -> Result<fsrs::SimulatorConfig> {
+    if self.post_scheduling_fn.is_some() || self.review_priority_fn.is_some() {
+      return Err(napi_error(
+        "postSchedulingFn and reviewPriorityFn are only supported by simulateAsync and computeOptimalRetention",
+      ));
+    }
+    if let Some(easy_days) = &self.easy_days {
+      easy_days.validate()?;
+    }
     Ok(fsrs::SimulatorConfig {
       deck_size: self.deck_size as usize,
       learn_span: self.learn_span as usize,
@@ -683,7 +1476,7 @@ impl SimulatorConfig {
       review_limit: self.review_limit as usize,
       new_cards_ignore_review_limit: self.new_cards_ignore_review_limit,
       suspend_after_lapses: self.suspend_after_lapses,
-      post_scheduling_fn: None,
+      post_scheduling_fn: self.easy_days.map(fuzz::easy_days_post_scheduling_fn),
       review_priority_fn: None,
       learning_step_transitions: matrix_to_array(
         self.learning_step_transitions,
@@ -698,6 +1491,39 @@ impl SimulatorConfig {
       relearning_step_count: self.relearning_step_count as usize,
     })
   }
+
+  /// Like `into_fsrs`, but also turns the JS hooks into simulator callbacks. Errors they
+  /// raise are collected in the returned `HookError`.
+  fn into_fsrs_with_hooks(
+    mut self,
+    env: Env,
+  ) -> Result<(fsrs::SimulatorConfig, simulator_hooks::HookError)> {
+    if self.post_scheduling_fn.is_some() && self.easy_days.is_some() {
+      return Err(napi_error(
+        "easyDays can't be combined with postSchedulingFn",
+      ));
+    }
+    let hook_error = simulator_hooks::HookError::default();
+    let post_scheduling_fn = self
+      .post_scheduling_fn
+      .take()
+      .map(|callback| simulator_hooks::post_scheduling_fn(env, callback, hook_error.clone()))
+      .transpose()?;
+    let review_priority_fn = self
+      .review_priority_fn
+      .take()
+      .map(|callback| simulator_hooks::review_priority_fn(env, callback, hook_error.clone()))
+      .transpose()?;
+    let config = self.into_fsrs()?;
+    Ok((
+      fsrs::SimulatorConfig {
+        post_scheduling_fn: post_scheduling_fn.or(config.post_scheduling_fn),
+        review_priority_fn,
+        ..config
+      },
+      hook_error,
+    ))
+  }
 }
 
 impl From<fsrs::SimulatorConfig> for SimulatorConfig {
@@ -718,10 +1544,134 @@ impl From<fsrs::SimulatorConfig> for SimulatorConfig {
       learning_step_count: config.learning_step_count as u32,
       relearning_step_count: config.relearning_step_count as u32,
       suspend_after_lapses: config.suspend_after_lapses,
+      post_scheduling_fn: None,
+      review_priority_fn: None,
+      easy_days: None,
+    }
+  }
+}
+
+/// A card as the simulator sees it. Days are counted from the start of the simulation.
+#[napi(object)]
+pub struct SimulatorCard {
+  /// The id of an {@link ExistingCard}. Cards introduced by the simulation have ids of 0
+  /// and below.
+  pub id: i64,
+  pub difficulty: f64,
+  pub stability: f64,
+  pub last_date: f64,
+  pub due: f64,
+  pub interval: f64,
+  pub lapses: u32,
+  pub desired_retention: f64,
+}
+
+impl From<&fsrs::Card> for SimulatorCard {
+  fn from(card: &fsrs::Card) -> Self {
+    Self {
+      id: card.id,
+      difficulty: card.difficulty as f64,
+      stability: card.stability as f64,
+      last_date: card.last_date as f64,
+      due: card.due as f64,
+      interval: card.interval as f64,
+      lapses: card.lapses,
+      desired_retention: card.desired_retention as f64,
     }
   }
 }
 
+/// A card of an existing collection to start a simulation from. Days are relative to the
+/// first simulated day, so reviews in the past have a negative `lastDate`.
+#[napi(object)]
+pub struct ExistingCard {
+  /// Passed to the simulator hooks. Must be 1 or more, as the simulation numbers the cards
+  /// it introduces from 0 downwards.
+  pub id: i64,
+  pub stability: f64,
+  pub difficulty: f64,
+  pub last_date: f64,
+  /// Overdue cards may have a negative due day, they are reviewed on the first day.
+  pub due: f64,
+  /// Defaults to `due - lastDate`.
+  pub interval: Option<f64>,
+  pub lapses: Option<u32>,
+  /// Defaults to the desired retention of the simulation.
+  pub desired_retention: Option<f64>,
+}
+
+/// Converts existing cards for `fsrs::simulate`, which expects them to carry the filled parameters.
+fn existing_cards_to_fsrs(
+  cards: Option<Vec<ExistingCard>>,
+  parameters: &[f32],
+  desired_retention: f32,
+) -> Result<Option<Vec<fsrs::Card>>> {
+  let Some(cards) = cards else {
+    return Ok(None);
+  };
+  let parameters =
+    Arc::new(fsrs::check_and_fill_parameters(parameters).map_err(|e| fsrs_error("simulate", e))?);
+  cards
+    .into_iter()
+    .map(|card| {
+      if card.id < 1 {
+        return Err(napi_error("ExistingCard id must be 1 or more"));
+      }
+      if !(card.stability > 0.0 && card.stability.is_finite()) {
+        return Err(napi_error(
+          "ExistingCard stability must be a positive number",
+        ));
+      }
+      if !(1.0..=10.0).contains(&card.difficulty) {
+        return Err(napi_error(
+          "ExistingCard difficulty must be between 1 and 10",
+        ));
+      }
+      // The simulator asserts that no card is due before its last review
+      if !card.last_date.is_finite() || !card.due.is_finite() || card.due < card.last_date {
+        return Err(napi_error(
+          "ExistingCard due must be a finite day no earlier than lastDate",
+        ));
+      }
+      if card
+        .interval
+        .is_some_and(|interval| interval.is_nan() || interval < 0.0)
+      {
+        return Err(napi_error(
+          "ExistingCard interval must be a non-negative number",
+        ));
+      }
+      Ok(fsrs::Card {
+        id: card.id,
+        difficulty: card.difficulty as f32,
+        stability: card.stability as f32,
+        last_date: card.last_date as f32,
+        due: card.due as f32,
+        interval: card.interval.unwrap_or(card.due - card.last_date) as f32,
+        lapses: card.lapses.unwrap_or_default(),
+        desired_retention: card
+          .desired_retention
+          .map_or(desired_retention, |value| value as f32),
+        parameters: Arc::clone(&parameters),
+      })
+    })
+    .collect::<Result<Vec<_>>>()
+    .map(Some)
+}
+
+/// What `postSchedulingFn` gets to decide the interval of a card that was just reviewed.
+#[napi(object)]
+pub struct PostSchedulingContext {
+  /// The card, with the interval the simulator scheduled.
+  pub card: SimulatorCard,
+  pub max_interval: f64,
+  pub today: u32,
+  /// The number of cards due on each day of the simulation.
+  pub due_counts_per_day: Vec<u32>,
+  /// A number in [0, 1) from the simulation's seeded generator, e.g. for fuzz.
+  pub random: f64,
+}
+
 #[napi(object)]
 pub struct ModelEvaluation {
   pub log_loss: f64,
@@ -748,79 +1698,525 @@ pub struct ComputeParametersOption {
   pub card_ids: Option<Vec<JsNumber>>,
   /// Optional optimizer hyperparameters
   pub training_config: Option<TrainingConfig>,
+  /// Called whenever training progress changes. While training, the value also
+  /// says which split and epoch changed and how many items of that epoch are done.
+  /// fsrs only exposes training progress as shared state, so it is checked every
+  /// `timeout` milliseconds, and the training and validation loss are not reported.
   #[napi(
-    ts_type = "(err: Error | null , value: { current: number, total: number, percent: number }) => void"
+    ts_type = "(err: Error | null , value: { current: number, total: number, percent: number, splitIndex?: number, epoch?: number, epochTotal?: number, itemsProcessed?: number, itemsTotal?: number }) => void"
   )]
   pub progress: Option<JsFunction>,
+  /// How often, in milliseconds, training progress is checked for changes. Defaults to 500.
   #[napi(ts_type = "number")]
   pub timeout: Option<JsNumber>,
+  /// Stops training between batches once aborted. The promise then rejects with
+  /// an error whose `code` is `"Cancelled"` and `message` is `"AbortError"`.
+  /// Honoured by `computeParameters` and `evaluateWithTimeSeriesSplitsAsync`.
+  #[napi(ts_type = "AbortSignal")]
+  pub signal: Option<JsObject>,
+}
+
+#[napi(object)]
+pub struct EvaluateOption {
+  #[napi(
+    ts_type = "(err: Error | null , value: { current: number, total: number, percent: number }) => void"
+  )]
+  pub progress: Option<JsFunction>,
+}
+
+#[napi(object)]
+pub struct OptimalRetentionOption {
+  /// Called before each retention the search evaluates, with the number evaluated so
+  /// far as `current`. The search doesn't know in advance how many retentions it needs,
+  /// so `total` and `percent` are 0.
+  #[napi(
+    ts_type = "(err: Error | null , value: { current: number, total: number, percent: number }) => void"
+  )]
+  pub progress: Option<JsFunction>,
+  /// Stops the search before the next retention once aborted. The promise then rejects
+  /// with an error whose `code` is `"Cancelled"` and `message` is `"AbortError"`.
+  #[napi(ts_type = "AbortSignal")]
+  pub signal: Option<JsObject>,
+}
+
+/// The average cost per memorized card the simulator found for a desired retention.
+#[napi(object)]
+pub struct RetentionCost {
+  pub desired_retention: f64,
+  pub cost: f64,
+}
+
+#[napi(object)]
+pub struct OptimalRetention {
+  /// The evaluated desired retention with the lowest cost.
+  pub desired_retention: f64,
+  /// Every evaluated desired retention, in ascending order.
+  pub cost_curve: Vec<RetentionCost>,
+}
+
+/// A card to reschedule. Days are counted from any fixed day, the same one as `today`.
+#[napi(object)]
+pub struct RescheduleCard {
+  pub id: i64,
+  /// The card's review history, replayed with the new parameters.
+  pub reviews: Vec<FSRSReviewJson>,
+  /// The memory state before the first of `reviews`, as in {@link FSRS.memoryState}.
+  pub starting_state: Option<MemoryStateJson>,
+  pub last_review: i64,
+  pub due: i64,
+}
+
+#[napi(object)]
+pub struct RescheduleOption {
+  /// Defaults to 36500.
+  pub maximum_interval: Option<u32>,
+  /// Spread the intervals with {@link fuzzInterval}, seeded by the card id and its
+  /// number of reviews. Defaults to false.
+  pub enable_fuzz: Option<bool>,
+}
+
+#[napi(object)]
+pub struct RescheduledCard {
+  pub id: i64,
+  pub memory: MemoryStateJson,
+  /// Days from the last review to the new due day.
+  pub interval: u32,
+  pub due: i64,
+  /// How many days the card moved, negative if it is now due earlier.
+  pub days_moved: i64,
+}
+
+#[napi(object)]
+pub struct FuzzOption {
+  /// Together with `reviewCount`, seeds the fuzz so a card is always fuzzed the same way.
+  pub card_id: i64,
+  /// How many times the card has been reviewed before.
+  pub review_count: u32,
+  /// Defaults to 36500.
+  pub maximum_interval: Option<u32>,
+}
+
+#[napi(object)]
+pub struct LoadBalanceOption {
+  /// The number of cards due on each day, starting with the day of the review.
+  pub due_counts: Uint32Array,
+  /// Defaults to 36500.
+  pub maximum_interval: Option<u32>,
+  pub easy_days: Option<EasyDays>,
+}
+
+/// How much to review on each day of the week, so intervals are moved away from days
+/// with less time to study.
+#[napi(object)]
+#[derive(Clone)]
+pub struct EasyDays {
+  /// Seven load multipliers, Monday first. 1 is a normal day, 0.5 counts the cards due
+  /// as twice as many, and 0 avoids the day unless no other day is acceptable.
+  pub multipliers: Vec<f64>,
+  /// The weekday of the day of the review, or of the first simulated day, 0 for Monday.
+  pub weekday: u32,
+}
+
+impl EasyDays {
+  fn validate(&self) -> Result<()> {
+    if self.multipliers.len() != 7 {
+      return Err(napi_error("easyDays.multipliers must have 7 values"));
+    }
+    if self
+      .multipliers
+      .iter()
+      .any(|&multiplier| !(0.0..=1.0).contains(&multiplier))
+    {
+      return Err(napi_error("easyDays.multipliers must be between 0 and 1"));
+    }
+    if self.weekday > 6 {
+      return Err(napi_error("easyDays.weekday must be between 0 and 6"));
+    }
+    Ok(())
+  }
+
+  /// The multiplier of the day `days` after `weekday`.
+  fn multiplier(&self, days: usize) -> f64 {
+    self.multipliers[(self.weekday as usize + days) % 7]
+  }
 }
 
+fn load_balanced_days(
+  interval: f64,
+  elapsed_days: u32,
+  due_counts: &[u32],
+  maximum_interval: Option<u32>,
+  easy_days: Option<&EasyDays>,
+) -> u32 {
+  fuzz::load_balanced_days(
+    interval,
+    elapsed_days,
+    maximum_interval.unwrap_or(fuzz::DEFAULT_MAXIMUM_INTERVAL),
+    |day| {
+      fuzz::day_load(
+        due_counts.get(day as usize).copied().unwrap_or(0) as usize,
+        easy_days.map_or(1.0, |easy_days| easy_days.multiplier(day as usize)),
+      )
+    },
+  )
+}
+
+/// The inclusive range of whole days an interval may be fuzzed to.
+#[napi(object)]
+pub struct FuzzRange {
+  pub min: u32,
+  pub max: u32,
+}
+
+/// Decides which day a review timestamp falls on.
+#[napi(object)]
+pub struct DayCutoffOption {
+  /// Hour of the day at which the next day starts. Defaults to 4.
+  pub next_day_starts_at: Option<u32>,
+  /// Offset of the reviewer's time zone from UTC in minutes, e.g. 480 for UTC+8. Defaults to 0.
+  pub utc_offset_minutes: Option<i32>,
+}
+
+#[napi(object)]
+pub struct ReviewEvent {
+  pub card_id: i64,
+  /// Unix timestamp of the review in milliseconds
+  pub timestamp_ms: i64,
+  /// 1-4
+  pub rating: u32,
+}
+
+#[napi(js_name = "RevlogImportResult")]
+pub struct RevlogImportResult {
+  items: Arc<Vec<fsrs::FSRSItem>>,
+  card_ids: Vec<i64>,
+}
+
+#[napi]
+impl RevlogImportResult {
+  #[napi(getter)]
+  pub fn items(&self) -> Vec<FSRSItem> {
+    self.items.iter().cloned().map(FSRSItem).collect()
+  }
+
+  /// The card id of each item, to pass as `cardIds` to {@link FSRS.computeParameters}.
+  #[napi(getter)]
+  pub fn card_ids(&self) -> Vec<i64> {
+    self.card_ids.clone()
+  }
+
+  /// The items and their card ids as a {@link Dataset}.
+  #[napi]
+  pub fn to_dataset(&self) -> Dataset {
+    Dataset::from_fsrs(Arc::clone(&self.items), Some(self.card_ids.clone()))
+  }
+}
+
+/// Each filtered list is a {@link Dataset}, with the card ids of the kept items, when a
+/// `Dataset` was passed in for it.
 #[napi(js_name = "FilterOutlierResult")]
 pub struct FilterOutlierResult {
-  dataset_for_initialization: Vec<fsrs::FSRSItem>,
-  trainset: Vec<fsrs::FSRSItem>,
+  dataset_for_initialization: Either<Vec<fsrs::FSRSItem>, Dataset>,
+  trainset: Either<Vec<fsrs::FSRSItem>, Dataset>,
+}
+
+fn filtered_items(items: &Either<Vec<fsrs::FSRSItem>, Dataset>) -> Either<Vec<FSRSItem>, Dataset> {
+  match items {
+    Either::A(items) => Either::A(items.iter().cloned().map(FSRSItem).collect()),
+    Either::B(dataset) => Either::B(dataset.clone()),
+  }
 }
 
 #[napi]
 impl FilterOutlierResult {
-  #[napi(getter)]
-  pub fn dataset_for_initialization(&self) -> Vec<FSRSItem> {
-    self
-      .dataset_for_initialization
-      .iter()
-      .cloned()
-      .map(FSRSItem)
-      .collect()
+  #[napi(getter, ts_return_type = "Array<FSRSItem> | Dataset")]
+  pub fn dataset_for_initialization(&self) -> Either<Vec<FSRSItem>, Dataset> {
+    filtered_items(&self.dataset_for_initialization)
   }
 
-  #[napi(getter)]
-  pub fn trainset(&self) -> Vec<FSRSItem> {
-    self.trainset.iter().cloned().map(FSRSItem).collect()
+  #[napi(getter, ts_return_type = "Array<FSRSItem> | Dataset")]
+  pub fn trainset(&self) -> Either<Vec<FSRSItem>, Dataset> {
+    filtered_items(&self.trainset)
   }
 }
 
+/// Intervals below 2.5 days are only rounded. Longer ones are spread by 15% of the days
+/// between 2.5 and 7, 10% of the days between 7 and 20 and 5% of the days beyond, plus
+/// one day, but never to 1 day or to `elapsedDays` or less.
+#[napi]
+pub fn fuzz_range(interval: f64, elapsed_days: u32, maximum_interval: Option<u32>) -> FuzzRange {
+  let (min, max) = fuzz::fuzz_bounds(
+    interval,
+    elapsed_days,
+    maximum_interval.unwrap_or(fuzz::DEFAULT_MAXIMUM_INTERVAL),
+  );
+  FuzzRange { min, max }
+}
+
+/// Round `interval` to a day within its {@link fuzzRange}, chosen deterministically
+/// from the card id and review count.
+#[napi]
+pub fn fuzz_interval(interval: f64, elapsed_days: u32, options: FuzzOption) -> u32 {
+  fuzz::fuzzed_days(interval, elapsed_days, &options)
+}
+
+/// The day within the {@link fuzzRange} of `interval` with the fewest due cards, where
+/// `dueCounts[i]` is the number of cards due `i` days from now, weighted by `easyDays`.
+/// Ties go to the day closest to `interval`, then to the earlier one.
+#[napi]
+pub fn load_balance_interval(
+  interval: f64,
+  elapsed_days: u32,
+  due_counts: Uint32Array,
+  maximum_interval: Option<u32>,
+  easy_days: Option<EasyDays>,
+) -> Result<u32> {
+  if let Some(easy_days) = &easy_days {
+    easy_days.validate()?;
+  }
+  Ok(load_balanced_days(
+    interval,
+    elapsed_days,
+    &due_counts,
+    maximum_interval,
+    easy_days.as_ref(),
+  ))
+}
+
+/// Replay the review histories of `cards` with `newParameters`, and schedule them from
+/// their last review with the resulting memory states. Cards are never rescheduled
+/// before `today`.
+#[napi]
+pub fn reschedule_cards(
+  mut cards: Vec<RescheduleCard>,
+  new_parameters: Vec<JsNumber>,
+  desired_retention: f64,
+  today: i64,
+  options: Option<RescheduleOption>,
+) -> Result<Vec<RescheduledCard>> {
+  if !(desired_retention > 0.0 && desired_retention < 1.0) {
+    return Err(napi_error("desiredRetention must be between 0 and 1"));
+  }
+  if let Some(card) = cards.iter().find(|card| card.reviews.is_empty()) {
+    return Err(napi_error(format!("Card {} has no reviews", card.id)));
+  }
+  let maximum_interval = options
+    .as_ref()
+    .and_then(|x| x.maximum_interval)
+    .unwrap_or(fuzz::DEFAULT_MAXIMUM_INTERVAL)
+    .max(1);
+  let enable_fuzz = options.and_then(|x| x.enable_fuzz).unwrap_or(false);
+  let model = fsrs::FSRS::new(&vec_to_f32(new_parameters)?)
+    .map_err(|e| napi_error(format!("FSRS initialization failed: {e}")))?;
+  let (items, starting_states) = cards
+    .iter_mut()
+    .map(|card| {
+      let reviews = card
+        .reviews
+        .iter()
+        .map(|review| fsrs::FSRSReview {
+          rating: review.rating,
+          delta_t: review.delta_t,
+        })
+        .collect();
+      (
+        fsrs::FSRSItem { reviews },
+        card.starting_state.take().map(fsrs::MemoryState::from),
+      )
+    })
+    .unzip();
+  let memory_states = model
+    .memory_state_batch(items, starting_states)
+    .map_err(|e| fsrs_error("memoryStateBatch", e))?;
+
+  cards
+    .into_iter()
+    .zip(memory_states)
+    .map(|(card, memory)| {
+      let out_of_range = || napi_error(format!("Card {} is scheduled out of range", card.id));
+      let interval =
+        model.next_interval(Some(memory.stability), desired_retention as f32, 1) as f64;
+      if !interval.is_finite() {
+        return Err(napi_error(format!(
+          "Card {} has no finite interval",
+          card.id
+        )));
+      }
+      let interval = if enable_fuzz {
+        let elapsed_days = today
+          .checked_sub(card.last_review)
+          .and_then(|days| u32::try_from(days.max(0)).ok())
+          .ok_or_else(out_of_range)?;
+        fuzz::fuzzed_days(
+          interval,
+          elapsed_days,
+          &FuzzOption {
+            card_id: card.id,
+            review_count: card.reviews.len() as u32,
+            maximum_interval: Some(maximum_interval),
+          },
+        )
+      } else {
+        interval.round().clamp(1.0, maximum_interval as f64) as u32
+      };
+      let due = card
+        .last_review
+        .checked_add(interval as i64)
+        .ok_or_else(out_of_range)?
+        .max(today);
+      Ok(RescheduledCard {
+        id: card.id,
+        memory: (&memory).into(),
+        interval: due
+          .checked_sub(card.last_review)
+          .and_then(|days| u32::try_from(days).ok())
+          .ok_or_else(out_of_range)?,
+        due,
+        days_moved: due.checked_sub(card.due).ok_or_else(out_of_range)?,
+      })
+    })
+    .collect()
+}
+
 #[napi]
 pub fn default_simulator_config() -> SimulatorConfig {
   fsrs::SimulatorConfig::default().into()
 }
 
+fn seed_from_js(seed: Option<&JsNumber>) -> Result<Option<u64>> {
+  seed
+    .map(|seed| {
+      js_number_to_u64(seed).ok_or_else(|| napi_error("seed must be a non-negative integer"))
+    })
+    .transpose()
+}
+
+/// Simulate reviewing a deck of `config.deckSize` cards over `config.learnSpan` days.
+///
+/// `existingCards`, if given, are the deck's already-learned cards and count toward
+/// `deckSize`. The rest of the deck is introduced as new cards.
 #[napi]
 pub fn simulate(
   w: Vec<JsNumber>,
   desired_retention: f64,
   config: Option<SimulatorConfig>,
   seed: Option<JsNumber>,
+  existing_cards: Option<Vec<ExistingCard>>,
 ) -> Result<SimulationResult> {
   let config = match config {
     Some(config) => config.into_fsrs()?,
     None => fsrs::SimulatorConfig::default(),
   };
-  let seed = seed
+  let seed = seed_from_js(seed.as_ref())?;
+  let w = vec_to_f32(w)?;
+  let existing_cards = existing_cards_to_fsrs(existing_cards, &w, desired_retention as f32)?;
+
+  fsrs::simulate(&config, &w, desired_retention as f32, seed, existing_cards)
+    .map(SimulationResult::from)
+    .map_err(|e| fsrs_error("simulate", e))
+}
+
+/// Simulate `config` with its `easyDays` and with all multipliers set to 1, using the
+/// same seed, to show how they affect retention and cost. Both simulations are load
+/// balanced, so the difference is only due to the easy days.
+#[napi]
+pub fn compare_easy_days(
+  w: Vec<JsNumber>,
+  desired_retention: f64,
+  config: SimulatorConfig,
+  seed: Option<JsNumber>,
+) -> Result<EasyDaysComparison> {
+  let weekday = config
+    .easy_days
     .as_ref()
-    .map(|seed| {
-      js_number_to_u64(seed).ok_or_else(|| napi_error("seed must be a non-negative integer"))
-    })
-    .transpose()?;
+    .map(|easy_days| easy_days.weekday)
+    .ok_or_else(|| napi_error("config.easyDays is required"))?;
+  let mut config = config.into_fsrs()?;
+  let seed = seed_from_js(seed.as_ref())?;
+  let w = vec_to_f32(w)?;
+  let simulate = |config: &fsrs::SimulatorConfig| {
+    fsrs::simulate(config, &w, desired_retention as f32, seed, None)
+      .map(|result| SimulationSummary::new(&result, weekday))
+      .map_err(|e| fsrs_error("simulate", e))
+  };
+  let easy_days = simulate(&config)?;
+  config.post_scheduling_fn = Some(fuzz::easy_days_post_scheduling_fn(EasyDays {
+    multipliers: vec![1.0; 7],
+    weekday,
+  }));
+  Ok(EasyDaysComparison {
+    baseline: simulate(&config)?,
+    easy_days,
+  })
+}
 
-  fsrs::simulate(
-    &config,
-    &vec_to_f32(w)?,
-    desired_retention as f32,
-    seed,
-    None,
-  )
-  .map(SimulationResult::from)
-  .map_err(|e| fsrs_error("simulate", e))
+/// Like {@link simulate}, but runs on a background thread, which lets the simulator call
+/// the `postSchedulingFn` and `reviewPriorityFn` hooks of the config.
+#[napi(ts_return_type = "Promise<SimulationResult>")]
+pub fn simulate_async(
+  env: Env,
+  w: Vec<JsNumber>,
+  desired_retention: f64,
+  config: Option<SimulatorConfig>,
+  seed: Option<JsNumber>,
+  existing_cards: Option<Vec<ExistingCard>>,
+) -> Result<AsyncTask<SimulateTask>> {
+  let (config, hook_error) = match config {
+    Some(config) => config.into_fsrs_with_hooks(env)?,
+    None => Default::default(),
+  };
+  let parameters = vec_to_f32(w)?;
+  let existing_cards =
+    existing_cards_to_fsrs(existing_cards, &parameters, desired_retention as f32)?;
+  Ok(AsyncTask::new(SimulateTask {
+    config,
+    parameters,
+    desired_retention: desired_retention as f32,
+    seed: seed_from_js(seed.as_ref())?,
+    existing_cards,
+    hook_error,
+  }))
+}
+
+/// Find the desired retention that minimizes the simulated workload per memorized card.
+///
+/// fsrs' search runs the simulator for a series of retentions between 0.70 and 0.95 on a
+/// background thread, narrowing in on the cheapest one.
+#[napi(ts_return_type = "Promise<OptimalRetention>")]
+pub fn compute_optimal_retention(
+  env: Env,
+  parameters: Vec<JsNumber>,
+  config: Option<SimulatorConfig>,
+  options: Option<OptimalRetentionOption>,
+) -> Result<AsyncTask<OptimalRetentionTask>> {
+  let (config, hook_error) = match config {
+    Some(config) => config.into_fsrs_with_hooks(env)?,
+    None => Default::default(),
+  };
+  let parameters = vec_to_f32(parameters)?;
+  fsrs::check_and_fill_parameters(&parameters)
+    .map_err(|e| fsrs_error("computeOptimalRetention", e))?;
+  let progress_callback =
+    progress_callback_from_js(options.as_ref().and_then(|x| x.progress.as_ref()))?;
+  let (progress_state, abort_listener) =
+    progress_state_from_signal(env, options.as_ref().and_then(|x| x.signal.as_ref()))?;
+  Ok(AsyncTask::new(OptimalRetentionTask {
+    config,
+    parameters,
+    hook_error,
+    progress_callback,
+    progress_state,
+    abort_listener,
+  }))
 }
 
 #[napi]
 pub fn evaluate_with_time_series_splits(
-  train_set: Vec<&FSRSItem>,
+  #[napi(ts_arg_type = "Array<FSRSItem> | Dataset")] train_set: TrainSet,
   #[napi(ts_arg_type = "ComputeParametersOption")] options: Option<ComputeParametersOption>,
 ) -> Result<ModelEvaluation> {
   let result = fsrs::evaluate_with_time_series_splits(
-    compute_parameters_input(train_set, options.as_ref(), None)?,
+    compute_parameters_input(train_set, options.as_ref(), None)?.into_fsrs(),
     |_| true,
   )
   .map_err(|e| fsrs_error("evaluateWithTimeSeriesSplits", e))?;
@@ -828,35 +2224,112 @@ pub fn evaluate_with_time_series_splits(
   Ok(result.into())
 }
 
+/// Like {@link evaluateWithTimeSeriesSplits}, but runs on a background thread.
+/// `progress` is called once per finished split.
+#[napi(ts_return_type = "Promise<ModelEvaluation>")]
+pub fn evaluate_with_time_series_splits_async(
+  env: Env,
+  #[napi(ts_arg_type = "Array<FSRSItem> | Dataset")] train_set: TrainSet,
+  #[napi(ts_arg_type = "ComputeParametersOption")] options: Option<ComputeParametersOption>,
+) -> Result<AsyncTask<EvaluateWithTimeSeriesSplitsTask>> {
+  let input = compute_parameters_input(train_set, options.as_ref(), None)?;
+  let progress_callback =
+    progress_callback_from_js(options.as_ref().and_then(|x| x.progress.as_ref()))?;
+  let (progress_state, abort_listener) =
+    progress_state_from_signal(env, options.as_ref().and_then(|x| x.signal.as_ref()))?;
+  Ok(AsyncTask::new(EvaluateWithTimeSeriesSplitsTask {
+    input,
+    progress_callback,
+    progress_state,
+    abort_listener,
+  }))
+}
+
 #[napi]
 pub fn filter_outlier(
-  dataset_for_initialization: Vec<&FSRSItem>,
-  trainset: Vec<&FSRSItem>,
+  #[napi(ts_arg_type = "Array<FSRSItem> | Dataset")] dataset_for_initialization: TrainSet,
+  #[napi(ts_arg_type = "Array<FSRSItem> | Dataset")] trainset: TrainSet,
 ) -> Result<FilterOutlierResult> {
+  let initialization_is_dataset = matches!(dataset_for_initialization, Either::B(_));
+  let trainset_is_dataset = matches!(trainset, Either::B(_));
+  let (dataset_for_initialization, initialization_card_ids) =
+    train_set_to_fsrs(dataset_for_initialization);
+  let (trainset, trainset_card_ids) = train_set_to_fsrs(trainset);
   if dataset_for_initialization
     .iter()
     .chain(trainset.iter())
-    .any(|item| item.0.reviews.is_empty())
+    .any(|item| item.reviews.is_empty())
   {
     return Err(napi_error("FSRSItem reviews must not be empty"));
   }
 
-  let (dataset_for_initialization, trainset) = fsrs::filter_outlier(
-    dataset_for_initialization
-      .into_iter()
-      .map(|item| item.0.clone())
-      .collect(),
-    trainset.into_iter().map(|item| item.0.clone()).collect(),
+  // The unfiltered items are only needed to look up the card ids of the kept ones.
+  let initialization_card_ids =
+    initialization_card_ids.map(|card_ids| (Arc::clone(&dataset_for_initialization), card_ids));
+  let trainset_card_ids = trainset_card_ids.map(|card_ids| (Arc::clone(&trainset), card_ids));
+  let (filtered_initialization, filtered_trainset) = fsrs::filter_outlier(
+    Arc::unwrap_or_clone(dataset_for_initialization),
+    Arc::unwrap_or_clone(trainset),
   );
 
   Ok(FilterOutlierResult {
-    dataset_for_initialization,
-    trainset,
+    dataset_for_initialization: filtered_train_set(
+      filtered_initialization,
+      initialization_is_dataset,
+      initialization_card_ids,
+    )?,
+    trainset: filtered_train_set(filtered_trainset, trainset_is_dataset, trainset_card_ids)?,
   })
 }
 
+fn filtered_train_set(
+  filtered: Vec<fsrs::FSRSItem>,
+  is_dataset: bool,
+  card_ids: Option<(Arc<Vec<fsrs::FSRSItem>>, Vec<i64>)>,
+) -> Result<Either<Vec<fsrs::FSRSItem>, Dataset>> {
+  if !is_dataset {
+    return Ok(Either::A(filtered));
+  }
+  let card_ids = card_ids
+    .map(|(items, card_ids)| {
+      dataset::kept_card_ids(&items, &card_ids, &filtered)
+        .ok_or_else(|| napi_error("filterOutlier kept an item that was not passed in"))
+    })
+    .transpose()?;
+  Ok(Either::B(Dataset::from_fsrs(filtered, card_ids)))
+}
+
 #[napi]
 pub fn check_and_fill_parameters(parameters: Vec<JsNumber>) -> Result<Vec<f32>> {
   fsrs::check_and_fill_parameters(&vec_to_f32(parameters)?)
     .map_err(|e| fsrs_error("checkAndFillParameters", e))
 }
+
+/// Name the parameters. Like {@link checkAndFillParameters}, an empty array gives the
+/// defaults and older 17 or 19 parameter sets are converted.
+#[napi]
+pub fn parameters_from_array(parameters: Vec<JsNumber>) -> Result<Parameters> {
+  let parameters = fsrs::check_and_fill_parameters(&vec_to_f32(parameters)?)
+    .map_err(|e| fsrs_error("checkAndFillParameters", e))?;
+  Ok(Parameters::from(parameters.as_slice()))
+}
+
+/// The flat array taken by [`new FSRS()`]{@link FSRS.constructor} and the other functions.
+/// Every field must be a finite number.
+#[napi]
+pub fn parameters_to_array(parameters: Parameters) -> Result<Vec<f64>> {
+  let parameters = Vec::<f64>::from(&parameters);
+  if let Some(index) = parameters.iter().position(|value| !value.is_finite()) {
+    return Err(napi_error(format!(
+      "{} must be a finite number",
+      parameters::parameter_name(index)
+    )));
+  }
+  Ok(parameters)
+}
+
+/// The name, bounds, default and meaning of each parameter, in array order.
+#[napi]
+pub fn parameter_info() -> Vec<ParameterInfo> {
+  parameters::parameter_info()
+}
@@ -0,0 +1,54 @@
+use std::sync::{Arc, Mutex};
+
+/// Runs `fsrs::optimal_retention`, also recording the cost of each retention it evaluates.
+///
+/// `progress` is called with the number of retentions evaluated so far before each
+/// evaluation, and the search is interrupted when it returns `false`. Returns the optimal
+/// retention and every evaluated `(retention, cost)` pair, sorted by retention.
+pub(crate) fn cost_curve(
+  config: &fsrs::SimulatorConfig,
+  parameters: &[f32],
+  mut progress: impl FnMut(usize) -> bool + Send,
+) -> fsrs::Result<(f32, Vec<(f32, f32)>)> {
+  // The simulations of a retention run in parallel, so their costs are summed per
+  // retention, which every new card of a simulation is scheduled with.
+  let costs = Arc::new(Mutex::new(Vec::<(f32, f32, usize)>::new()));
+  let target = {
+    let costs = Arc::clone(&costs);
+    let cost_per_memorized_card = fsrs::CMRRTargetFn::default();
+    fsrs::CMRRTargetFn::new(move |result, parameters| {
+      let cost = cost_per_memorized_card(result, parameters);
+      if let Some(card) = result.cards.first() {
+        let mut costs = costs.lock().unwrap();
+        match costs
+          .iter_mut()
+          .find(|(retention, ..)| *retention == card.desired_retention)
+        {
+          Some((_, sum, count)) => {
+            *sum += cost;
+            *count += 1;
+          }
+          None => costs.push((card.desired_retention, cost, 1)),
+        }
+      }
+      cost
+    })
+  };
+
+  let desired_retention = fsrs::optimal_retention(
+    config,
+    parameters,
+    |item_progress| progress(item_progress.current - 1),
+    None,
+    Some(target),
+  )?;
+
+  let mut curve = costs
+    .lock()
+    .unwrap()
+    .iter()
+    .map(|&(retention, sum, count)| (retention, sum / count as f32))
+    .collect::<Vec<_>>();
+  curve.sort_by(|x, y| x.0.total_cmp(&y.0));
+  Ok((desired_retention, curve))
+}
@@ -0,0 +1,308 @@
+use napi_derive::napi;
+
+/// The FSRS-6 parameters by name. Stabilities are in days.
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct Parameters {
+  /// w0
+  pub initial_stability_again: f64,
+  /// w1
+  pub initial_stability_hard: f64,
+  /// w2
+  pub initial_stability_good: f64,
+  /// w3
+  pub initial_stability_easy: f64,
+  /// w4
+  pub initial_difficulty: f64,
+  /// w5
+  pub initial_difficulty_rating_factor: f64,
+  /// w6
+  pub difficulty_delta: f64,
+  /// w7
+  pub difficulty_mean_reversion: f64,
+  /// w8
+  pub recall_stability_factor: f64,
+  /// w9
+  pub recall_stability_decay: f64,
+  /// w10
+  pub recall_retrievability_factor: f64,
+  /// w11
+  pub forget_stability_factor: f64,
+  /// w12
+  pub forget_difficulty_exponent: f64,
+  /// w13
+  pub forget_stability_exponent: f64,
+  /// w14
+  pub forget_retrievability_factor: f64,
+  /// w15
+  pub hard_penalty: f64,
+  /// w16
+  pub easy_bonus: f64,
+  /// w17
+  pub short_term_stability_factor: f64,
+  /// w18
+  pub short_term_rating_offset: f64,
+  /// w19
+  pub short_term_stability_exponent: f64,
+  /// w20
+  pub decay: f64,
+}
+
+/// What a parameter means, and the range `new FSRS()` clips it to.
+#[napi(object)]
+pub struct ParameterInfo {
+  /// The name of the field in {@link Parameters}.
+  pub name: String,
+  /// The position in the flat array.
+  pub index: u32,
+  /// The lower bound. For `shortTermStabilityExponent` it is 0.01 rather than 0 when
+  /// training with short-term scheduling enabled.
+  pub min: f64,
+  /// The upper bound. For `shortTermStabilityFactor` and `shortTermRatingOffset` it
+  /// only holds for a single relearning step; with more, training lowers it depending on
+  /// the forget parameters.
+  pub max: f64,
+  pub default_value: f64,
+  pub description: String,
+}
+
+/// Name, bounds, default and description of each parameter, in array order. The bounds
+/// mirror fsrs' parameter clipper with its default single relearning step and short-term
+/// scheduling disabled. The defaults are those of `DEFAULT_PARAMETERS`, as `f64` like the
+/// bounds so that JS reads them without `f32` rounding.
+const PARAMETER_INFO: [(&str, f64, f64, f64, &str); 21] = [
+  (
+    "initialStabilityAgain",
+    0.001,
+    100.0,
+    0.212,
+    "Stability after a first Again",
+  ),
+  (
+    "initialStabilityHard",
+    0.001,
+    100.0,
+    1.2931,
+    "Stability after a first Hard",
+  ),
+  (
+    "initialStabilityGood",
+    0.001,
+    100.0,
+    2.3065,
+    "Stability after a first Good",
+  ),
+  (
+    "initialStabilityEasy",
+    0.001,
+    100.0,
+    8.2956,
+    "Stability after a first Easy",
+  ),
+  (
+    "initialDifficulty",
+    1.0,
+    10.0,
+    6.4133,
+    "Difficulty after a first Again",
+  ),
+  (
+    "initialDifficultyRatingFactor",
+    0.001,
+    4.0,
+    0.8334,
+    "How much the first rating changes the initial difficulty",
+  ),
+  (
+    "difficultyDelta",
+    0.001,
+    4.0,
+    3.0194,
+    "How much each rating changes the difficulty",
+  ),
+  (
+    "difficultyMeanReversion",
+    0.001,
+    0.75,
+    0.001,
+    "How strongly the difficulty returns to the initial difficulty of a first Easy",
+  ),
+  (
+    "recallStabilityFactor",
+    0.0,
+    4.5,
+    1.8722,
+    "Overall stability increase after a successful review",
+  ),
+  (
+    "recallStabilityDecay",
+    0.0,
+    0.8,
+    0.1666,
+    "How much less the stability increases when it is already high",
+  ),
+  (
+    "recallRetrievabilityFactor",
+    0.001,
+    3.5,
+    0.796,
+    "How much more the stability increases when the card was nearly forgotten",
+  ),
+  (
+    "forgetStabilityFactor",
+    0.001,
+    5.0,
+    1.4835,
+    "Overall stability after a lapse",
+  ),
+  (
+    "forgetDifficultyExponent",
+    0.001,
+    0.25,
+    0.0614,
+    "How much lower the stability is after a lapse of a difficult card",
+  ),
+  (
+    "forgetStabilityExponent",
+    0.001,
+    0.9,
+    0.2629,
+    "How much of the stability before a lapse is kept",
+  ),
+  (
+    "forgetRetrievabilityFactor",
+    0.0,
+    4.0,
+    1.6483,
+    "How much higher the stability is after a lapse of a card that was likely forgotten",
+  ),
+  (
+    "hardPenalty",
+    0.0,
+    1.0,
+    0.6014,
+    "Multiplies the stability increase of a Hard review",
+  ),
+  (
+    "easyBonus",
+    1.0,
+    6.0,
+    1.8729,
+    "Multiplies the stability increase of an Easy review",
+  ),
+  (
+    "shortTermStabilityFactor",
+    0.0,
+    2.0,
+    0.5425,
+    "How much same-day reviews change the stability",
+  ),
+  (
+    "shortTermRatingOffset",
+    0.0,
+    2.0,
+    0.0912,
+    "Shifts which ratings increase the stability in same-day reviews",
+  ),
+  (
+    "shortTermStabilityExponent",
+    0.0,
+    0.8,
+    0.0658,
+    "How much less same-day reviews increase a high stability",
+  ),
+  (
+    "decay",
+    0.1,
+    0.8,
+    0.1542,
+    "How quickly the forgetting curve falls off",
+  ),
+];
+
+/// Clip filled parameters to the bounds in `PARAMETER_INFO`, as `fsrs::FSRS::new` does.
+pub(crate) fn clip_parameters(parameters: &mut [f32]) {
+  for (parameter, &(_, min, max, ..)) in parameters.iter_mut().zip(&PARAMETER_INFO) {
+    *parameter = parameter.clamp(min as f32, max as f32);
+  }
+}
+
+/// The name of the parameter at `index` in the flat array.
+pub(crate) fn parameter_name(index: usize) -> &'static str {
+  PARAMETER_INFO[index].0
+}
+
+pub(crate) fn parameter_info() -> Vec<ParameterInfo> {
+  PARAMETER_INFO
+    .iter()
+    .enumerate()
+    .map(
+      |(index, &(name, min, max, default_value, description))| ParameterInfo {
+        name: name.to_string(),
+        index: index as u32,
+        min,
+        max,
+        default_value,
+        description: description.to_string(),
+      },
+    )
+    .collect()
+}
+
+/// `parameters` must be 21 filled parameters.
+impl From<&[f32]> for Parameters {
+  fn from(parameters: &[f32]) -> Self {
+    let w = |index: usize| parameters[index] as f64;
+    Self {
+      initial_stability_again: w(0),
+      initial_stability_hard: w(1),
+      initial_stability_good: w(2),
+      initial_stability_easy: w(3),
+      initial_difficulty: w(4),
+      initial_difficulty_rating_factor: w(5),
+      difficulty_delta: w(6),
+      difficulty_mean_reversion: w(7),
+      recall_stability_factor: w(8),
+      recall_stability_decay: w(9),
+      recall_retrievability_factor: w(10),
+      forget_stability_factor: w(11),
+      forget_difficulty_exponent: w(12),
+      forget_stability_exponent: w(13),
+      forget_retrievability_factor: w(14),
+      hard_penalty: w(15),
+      easy_bonus: w(16),
+      short_term_stability_factor: w(17),
+      short_term_rating_offset: w(18),
+      short_term_stability_exponent: w(19),
+      decay: w(20),
+    }
+  }
+}
+
+impl From<&Parameters> for Vec<f64> {
+  fn from(parameters: &Parameters) -> Self {
+    vec![
+      parameters.initial_stability_again,
+      parameters.initial_stability_hard,
+      parameters.initial_stability_good,
+      parameters.initial_stability_easy,
+      parameters.initial_difficulty,
+      parameters.initial_difficulty_rating_factor,
+      parameters.difficulty_delta,
+      parameters.difficulty_mean_reversion,
+      parameters.recall_stability_factor,
+      parameters.recall_stability_decay,
+      parameters.recall_retrievability_factor,
+      parameters.forget_stability_factor,
+      parameters.forget_difficulty_exponent,
+      parameters.forget_stability_exponent,
+      parameters.forget_retrievability_factor,
+      parameters.hard_penalty,
+      parameters.easy_bonus,
+      parameters.short_term_stability_factor,
+      parameters.short_term_rating_offset,
+      parameters.short_term_stability_exponent,
+      parameters.decay,
+    ]
+  }
+}
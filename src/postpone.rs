@@ -0,0 +1,139 @@
+use napi::bindgen_prelude::Result;
+use napi_derive::napi;
+
+use crate::{MemoryStateJson, napi_error};
+
+/// A scheduled card for {@link FSRS.postponeCards} and {@link FSRS.advanceCards}. Days
+/// are counted from any fixed day, the same one as `today`.
+#[napi(object)]
+pub struct DueCard {
+  pub id: i64,
+  pub memory: MemoryStateJson,
+  pub last_review: i64,
+  pub due: i64,
+}
+
+#[napi(object)]
+pub struct PostponeOption {
+  /// Postpone at most this many cards.
+  pub count: Option<u32>,
+  /// Only postpone cards whose retrievability on the new due day is at least this high.
+  pub minimum_retrievability: Option<f64>,
+}
+
+#[napi(object)]
+pub struct AdvanceOption {
+  /// Advance at most this many cards.
+  pub count: Option<u32>,
+  /// Only advance cards whose retrievability today is at most this high.
+  pub maximum_retrievability: Option<f64>,
+}
+
+#[napi(object)]
+pub struct DueChange {
+  pub id: i64,
+  pub due: i64,
+  /// The retrievability on the new due day.
+  pub retrievability: f64,
+}
+
+/// The share of its interval a postponed card is delayed by.
+const POSTPONE_FACTOR: f64 = 0.05;
+
+fn out_of_range(card: &DueCard) -> napi::Error {
+  napi_error(format!("Card {} is scheduled out of range", card.id))
+}
+
+/// The retrievability of `card` on `day`.
+fn retrievability_on(card: &DueCard, day: i64, decay: f32) -> Result<f64> {
+  if card.memory.stability.is_nan() || card.memory.stability <= 0.0 {
+    return Err(napi_error(format!(
+      "Card {} must have a positive stability",
+      card.id
+    )));
+  }
+  let state = fsrs::MemoryState {
+    stability: card.memory.stability as f32,
+    difficulty: card.memory.difficulty as f32,
+  };
+  let days_elapsed = day
+    .checked_sub(card.last_review)
+    .ok_or_else(|| out_of_range(card))?
+    .max(0) as f32;
+  Ok(fsrs::current_retrievability(state, days_elapsed, decay) as f64)
+}
+
+/// The changes of `candidates`, sorted by their key, up to `count` of them.
+fn take_changes(mut candidates: Vec<(f64, DueChange)>, count: Option<u32>) -> Vec<DueChange> {
+  candidates.sort_by(|x, y| x.0.total_cmp(&y.0));
+  candidates
+    .into_iter()
+    .take(count.map_or(usize::MAX, |count| count as usize))
+    .map(|(_, change)| change)
+    .collect()
+}
+
+/// The cards due by `today` to postpone, those losing the least retrievability first.
+pub(crate) fn postpone(
+  cards: &[DueCard],
+  today: i64,
+  options: Option<PostponeOption>,
+  decay: f32,
+) -> Result<Vec<DueChange>> {
+  let (count, minimum_retrievability) = match options {
+    Some(options) => (options.count, options.minimum_retrievability),
+    None => (None, None),
+  };
+  let mut candidates = Vec::new();
+  for card in cards.iter().filter(|card| card.due <= today) {
+    let interval = card
+      .due
+      .checked_sub(card.last_review)
+      .ok_or_else(|| out_of_range(card))?
+      .max(1) as f64;
+    let due = today
+      .checked_add(((interval * POSTPONE_FACTOR).round() as i64).max(1))
+      .ok_or_else(|| out_of_range(card))?;
+    let retrievability = retrievability_on(card, due, decay)?;
+    if minimum_retrievability.is_none_or(|minimum| retrievability >= minimum) {
+      let loss = retrievability_on(card, today, decay)? - retrievability;
+      candidates.push((
+        loss,
+        DueChange {
+          id: card.id,
+          due,
+          retrievability,
+        },
+      ));
+    }
+  }
+  Ok(take_changes(candidates, count))
+}
+
+/// The cards not due yet to review `today`, those with the lowest retrievability first.
+pub(crate) fn advance(
+  cards: &[DueCard],
+  today: i64,
+  options: Option<AdvanceOption>,
+  decay: f32,
+) -> Result<Vec<DueChange>> {
+  let (count, maximum_retrievability) = match options {
+    Some(options) => (options.count, options.maximum_retrievability),
+    None => (None, None),
+  };
+  let mut candidates = Vec::new();
+  for card in cards.iter().filter(|card| card.due > today) {
+    let retrievability = retrievability_on(card, today, decay)?;
+    if maximum_retrievability.is_none_or(|maximum| retrievability <= maximum) {
+      candidates.push((
+        retrievability,
+        DueChange {
+          id: card.id,
+          due: today,
+          retrievability,
+        },
+      ));
+    }
+  }
+  Ok(take_changes(candidates, count))
+}
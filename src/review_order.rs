@@ -0,0 +1,41 @@
+use napi_derive::napi;
+
+use crate::fuzz::splitmix64;
+
+/// The order of {@link FSRS.sortCards}.
+#[napi(string_enum)]
+#[derive(Debug, PartialEq, Eq)]
+pub enum ReviewOrder {
+  /// The cards most likely to be forgotten first.
+  RetrievabilityAscending,
+  RetrievabilityDescending,
+  /// The cards overdue by the largest share of their interval first.
+  RelativeOverdueness,
+  DifficultyAscending,
+  DifficultyDescending,
+  /// Shuffled by `seed`.
+  Random,
+}
+
+#[napi(object)]
+pub struct ReviewOrderOption {
+  /// The retention intervals are scheduled for, used by `RelativeOverdueness`.
+  /// Defaults to 0.9.
+  pub desired_retention: Option<f64>,
+  /// Seeds the `Random` order. Defaults to 0.
+  pub seed: Option<i64>,
+}
+
+/// Indices of `count` cards sorted by `key`, lowest first. Cards with the same key keep
+/// their order.
+pub(crate) fn sort_by_key(count: usize, key: impl Fn(usize) -> f64) -> Vec<u32> {
+  let keys = (0..count).map(key).collect::<Vec<_>>();
+  let mut indices = (0..count as u32).collect::<Vec<_>>();
+  indices.sort_by(|&a, &b| keys[a as usize].total_cmp(&keys[b as usize]));
+  indices
+}
+
+/// A key that shuffles cards the same way for the same seed on every platform.
+pub(crate) fn random_key(seed: i64, index: usize) -> f64 {
+  splitmix64(splitmix64(seed as u64) ^ index as u64) as f64
+}
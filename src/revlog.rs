@@ -0,0 +1,203 @@
+use napi::bindgen_prelude::Result;
+use std::collections::HashMap;
+
+use crate::napi_error;
+
+const MS_PER_MINUTE: i64 = 60 * 1000;
+const MS_PER_HOUR: i64 = 60 * MS_PER_MINUTE;
+const MS_PER_DAY: i64 = 24 * MS_PER_HOUR;
+
+/// Review states as exported in Anki's revlog.
+const STATE_NEW: u32 = 0;
+const STATE_LEARNING: u32 = 1;
+
+/// Decides which calendar day a review timestamp belongs to.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct DayCutoff {
+  /// Hour of the local day at which the next day starts, e.g. `4` in Anki.
+  pub next_day_starts_at: i64,
+  /// Offset of the local time zone from UTC, e.g. `480` for UTC+8.
+  pub utc_offset_minutes: i64,
+}
+
+impl DayCutoff {
+  pub(crate) fn new(
+    next_day_starts_at: Option<u32>,
+    utc_offset_minutes: Option<i32>,
+  ) -> Result<Self> {
+    let next_day_starts_at = next_day_starts_at.unwrap_or(4);
+    if next_day_starts_at > 23 {
+      return Err(napi_error(
+        "nextDayStartsAt must be an hour between 0 and 23",
+      ));
+    }
+    let utc_offset_minutes = utc_offset_minutes.unwrap_or(0);
+    if utc_offset_minutes.abs() > 14 * 60 {
+      return Err(napi_error("utcOffsetMinutes must be between -840 and 840"));
+    }
+    Ok(Self {
+      next_day_starts_at: next_day_starts_at.into(),
+      utc_offset_minutes: utc_offset_minutes.into(),
+    })
+  }
+
+  /// Days since the unix epoch, counted in local days starting at `next_day_starts_at`.
+  pub(crate) fn day(&self, timestamp_ms: i64) -> i64 {
+    (timestamp_ms + self.utc_offset_minutes * MS_PER_MINUTE - self.next_day_starts_at * MS_PER_HOUR)
+      .div_euclid(MS_PER_DAY)
+  }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RevlogEntry {
+  pub card_id: i64,
+  pub review_time: i64,
+  pub rating: u32,
+  /// `None` when the source has no review state, in which case the whole history is kept.
+  pub state: Option<u32>,
+}
+
+/// Parses a revlog CSV with a header row. `card_id`, `review_time` (unix ms) and
+/// `review_rating` columns are required, `review_state` is optional.
+pub(crate) fn parse_revlog_csv(content: &str) -> Result<Vec<RevlogEntry>> {
+  let mut lines = content
+    .lines()
+    .enumerate()
+    .filter(|(_, line)| !line.trim().is_empty());
+  let Some((_, header)) = lines.next() else {
+    return Ok(Vec::new());
+  };
+  let columns = split_csv_line(header);
+  let column = |name: &str| columns.iter().position(|column| *column == name);
+  let required = |name: &str| {
+    column(name).ok_or_else(|| napi_error(format!("revlog CSV is missing the {name} column")))
+  };
+  let card_id_column = required("card_id")?;
+  let review_time_column = required("review_time")?;
+  let rating_column = required("review_rating")?;
+  let state_column = column("review_state");
+
+  lines
+    .map(|(index, line)| {
+      let fields = split_csv_line(line);
+      let field = |column: usize, name: &str| {
+        fields
+          .get(column)
+          .copied()
+          .ok_or_else(|| napi_error(format!("revlog CSV line {} has no {name} value", index + 1)))
+      };
+      let parse_error = |name: &str| {
+        napi_error(format!(
+          "revlog CSV line {} has an invalid {name}",
+          index + 1
+        ))
+      };
+      Ok(RevlogEntry {
+        card_id: field(card_id_column, "card_id")?
+          .parse()
+          .map_err(|_| parse_error("card_id"))?,
+        review_time: field(review_time_column, "review_time")?
+          .parse()
+          .map_err(|_| parse_error("review_time"))?,
+        rating: field(rating_column, "review_rating")?
+          .parse()
+          .map_err(|_| parse_error("review_rating"))?,
+        state: state_column
+          .map(|column| {
+            field(column, "review_state")?
+              .parse()
+              .map_err(|_| parse_error("review_state"))
+          })
+          .transpose()?,
+      })
+    })
+    .collect()
+}
+
+fn split_csv_line(line: &str) -> Vec<&str> {
+  line
+    .split(',')
+    .map(|field| field.trim().trim_matches('"'))
+    .collect()
+}
+
+/// Drops everything before the last block of learning reviews, so a card that was
+/// reset and relearned only contributes its latest history. Empty if it never was learned.
+fn remove_revlog_before_last_learning(entries: &[RevlogEntry]) -> &[RevlogEntry] {
+  if entries.iter().any(|entry| entry.state.is_none()) {
+    return entries;
+  }
+  let is_learning = |entry: &RevlogEntry| matches!(entry.state, Some(STATE_NEW | STATE_LEARNING));
+  let Some(last_learning) = entries.iter().rposition(is_learning) else {
+    return &[];
+  };
+  let block_start = entries[..last_learning]
+    .iter()
+    .rposition(|entry| !is_learning(entry))
+    .map_or(0, |index| index + 1);
+  &entries[block_start..]
+}
+
+/// Expands one card's chronological reviews into one item per review on a new day.
+/// Returns each item with the timestamp of its last review.
+fn history_to_items(history: &[(i64, u32)], cutoff: DayCutoff) -> Vec<(i64, fsrs::FSRSItem)> {
+  let Some(&(first_time, _)) = history.first() else {
+    return Vec::new();
+  };
+  let mut reviews = Vec::with_capacity(history.len());
+  let mut items = Vec::new();
+  let mut last_day = cutoff.day(first_time);
+  for &(review_time, rating) in history {
+    let day = cutoff.day(review_time);
+    let delta_t = (day - last_day).max(0) as u32;
+    reviews.push(fsrs::FSRSReview { rating, delta_t });
+    if delta_t > 0 {
+      items.push((
+        review_time,
+        fsrs::FSRSItem {
+          reviews: reviews.clone(),
+        },
+      ));
+    }
+    last_day = day;
+  }
+  items
+}
+
+/// Groups reviews by card and converts them into items sorted by review time,
+/// together with the card id of each item.
+pub(crate) fn revlog_to_items(
+  entries: Vec<RevlogEntry>,
+  cutoff: DayCutoff,
+) -> (Vec<fsrs::FSRSItem>, Vec<i64>) {
+  let mut entries_by_card: HashMap<i64, Vec<RevlogEntry>> = HashMap::new();
+  for entry in entries
+    .into_iter()
+    .filter(|entry| (1..=4).contains(&entry.rating))
+  {
+    entries_by_card
+      .entry(entry.card_id)
+      .or_default()
+      .push(entry);
+  }
+
+  let mut items = Vec::new();
+  for (card_id, mut entries) in entries_by_card {
+    entries.sort_by_key(|entry| entry.review_time);
+    let history = remove_revlog_before_last_learning(&entries)
+      .iter()
+      .map(|entry| (entry.review_time, entry.rating))
+      .collect::<Vec<_>>();
+    items.extend(
+      history_to_items(&history, cutoff)
+        .into_iter()
+        .map(|(review_time, item)| (review_time, card_id, item)),
+    );
+  }
+  items.sort_by_key(|(review_time, card_id, _)| (*review_time, *card_id));
+
+  items
+    .into_iter()
+    .map(|(_, card_id, item)| (item, card_id))
+    .unzip()
+}
@@ -0,0 +1,329 @@
+use napi::JsNumber;
+use napi::bindgen_prelude::Result;
+use napi_derive::napi;
+
+use crate::fuzz;
+use crate::{FuzzOption, SimulatorConfig, fsrs_error, js_numbers_to_f32, napi_error};
+
+const MS_PER_MINUTE: i64 = 60 * 1000;
+const MS_PER_DAY: i64 = 24 * 60 * MS_PER_MINUTE;
+
+/// Anki's default steps, in minutes.
+const DEFAULT_LEARNING_STEPS: [f64; 2] = [1.0, 10.0];
+const DEFAULT_RELEARNING_STEPS: [f64; 1] = [10.0];
+
+#[napi(string_enum)]
+#[derive(Debug, PartialEq, Eq)]
+pub enum CardState {
+  New,
+  Learning,
+  Review,
+  Relearning,
+}
+
+/// The scheduling state of a card. Timestamps are unix milliseconds.
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct Card {
+  /// Seeds the interval fuzz, see {@link SchedulerOptions.enableFuzz}.
+  pub id: Option<i64>,
+  pub state: CardState,
+  /// The current learning or relearning step, unset in the other states.
+  pub step: Option<u32>,
+  /// Unset until the first review.
+  pub stability: Option<f64>,
+  /// Unset until the first review.
+  pub difficulty: Option<f64>,
+  pub due: i64,
+  pub last_review: Option<i64>,
+  /// Days from the last review to `due` while in review, 0 during (re)learning.
+  pub scheduled_days: u32,
+  pub reps: u32,
+  pub lapses: u32,
+}
+
+/// A record of one review, with the card as it was before the review.
+#[napi(object)]
+pub struct ReviewLog {
+  pub rating: u32,
+  pub state: CardState,
+  pub due: i64,
+  pub stability: Option<f64>,
+  pub difficulty: Option<f64>,
+  /// Whole days since the previous review.
+  pub elapsed_days: u32,
+  /// The interval the review scheduled, 0 if the card is in (re)learning.
+  pub scheduled_days: u32,
+  pub review_time: i64,
+}
+
+#[napi(object)]
+pub struct ReviewResult {
+  pub card: Card,
+  pub log: ReviewLog,
+}
+
+#[napi(object)]
+pub struct SchedulerOptions {
+  /// Defaults to 0.9.
+  pub desired_retention: Option<f64>,
+  /// The longest interval in days. Defaults to 36500.
+  pub maximum_interval: Option<u32>,
+  /// Delays in minutes between the reviews of a new card before it graduates to
+  /// day intervals. Defaults to `[1, 10]`. Empty to graduate on the first review.
+  pub learning_steps: Option<Vec<f64>>,
+  /// Delays in minutes between the reviews of a lapsed card before it returns to
+  /// day intervals. Defaults to `[10]`. Empty to keep lapsed cards in review.
+  pub relearning_steps: Option<Vec<f64>>,
+  /// Spread day intervals with {@link fuzzInterval}, seeded by the card id and its number
+  /// of reviews. Defaults to false.
+  pub enable_fuzz: Option<bool>,
+}
+
+fn validate_steps(steps: Option<Vec<f64>>, default: &[f64], name: &str) -> Result<Vec<f64>> {
+  let steps = steps.unwrap_or_else(|| default.to_vec());
+  if steps.iter().any(|&step| !(step > 0.0 && step.is_finite())) {
+    return Err(napi_error(format!(
+      "{name} must be positive numbers of minutes"
+    )));
+  }
+  Ok(steps)
+}
+
+/// Schedules cards through the New, Learning, Review and Relearning states, using
+/// {@link FSRS.nextStates} for memory states and review intervals.
+///
+/// New cards go through the learning steps and lapsed cards through the relearning steps.
+/// Again returns a card to the first step, Hard repeats the current step and Good moves
+/// to the next one, until Good on the last step or Easy graduates it to day intervals.
+#[napi(js_name = "Scheduler")]
+pub struct Scheduler {
+  model: fsrs::FSRS,
+  desired_retention: f32,
+  maximum_interval: u32,
+  learning_steps: Vec<f64>,
+  relearning_steps: Vec<f64>,
+  enable_fuzz: bool,
+}
+
+#[napi]
+impl Scheduler {
+  /// `parameters` are handled like in [`new FSRS()`]{@link FSRS.constructor}.
+  #[napi(constructor)]
+  pub fn new(parameters: Option<Vec<JsNumber>>, options: Option<SchedulerOptions>) -> Result<Self> {
+    let parameters = js_numbers_to_f32(parameters.unwrap_or_default())?;
+    let model = fsrs::FSRS::new(&parameters)
+      .map_err(|e| napi_error(format!("FSRS initialization failed: {e}")))?;
+    let desired_retention = options
+      .as_ref()
+      .and_then(|x| x.desired_retention)
+      .unwrap_or(0.9);
+    if !(desired_retention > 0.0 && desired_retention < 1.0) {
+      return Err(napi_error("desiredRetention must be between 0 and 1"));
+    }
+    let maximum_interval = options
+      .as_ref()
+      .and_then(|x| x.maximum_interval)
+      .unwrap_or(36500);
+    if maximum_interval == 0 {
+      return Err(napi_error("maximumInterval must be at least 1"));
+    }
+    let enable_fuzz = options
+      .as_ref()
+      .and_then(|x| x.enable_fuzz)
+      .unwrap_or(false);
+    let (learning_steps, relearning_steps) = match options {
+      Some(options) => (options.learning_steps, options.relearning_steps),
+      None => (None, None),
+    };
+    Ok(Self {
+      model,
+      desired_retention: desired_retention as f32,
+      maximum_interval,
+      learning_steps: validate_steps(learning_steps, &DEFAULT_LEARNING_STEPS, "learningSteps")?,
+      relearning_steps: validate_steps(
+        relearning_steps,
+        &DEFAULT_RELEARNING_STEPS,
+        "relearningSteps",
+      )?,
+      enable_fuzz,
+    })
+  }
+
+  #[napi(getter)]
+  pub fn desired_retention(&self) -> f64 {
+    self.desired_retention as f64
+  }
+
+  #[napi(getter)]
+  pub fn maximum_interval(&self) -> u32 {
+    self.maximum_interval
+  }
+
+  #[napi(getter)]
+  pub fn enable_fuzz(&self) -> bool {
+    self.enable_fuzz
+  }
+
+  #[napi(getter)]
+  pub fn learning_steps(&self) -> Vec<f64> {
+    self.learning_steps.clone()
+  }
+
+  #[napi(getter)]
+  pub fn relearning_steps(&self) -> Vec<f64> {
+    self.relearning_steps.clone()
+  }
+
+  /// The default simulator config with the step counts and maximum interval of this
+  /// scheduler, so simulations graduate cards by the same rules.
+  #[napi]
+  pub fn simulator_config(&self) -> SimulatorConfig {
+    fsrs::SimulatorConfig {
+      max_ivl: self.maximum_interval as f32,
+      learning_step_count: self.learning_steps.len(),
+      relearning_step_count: self.relearning_steps.len(),
+      ..Default::default()
+    }
+    .into()
+  }
+
+  /// A card that has never been reviewed, due at `now`.
+  #[napi]
+  pub fn new_card(&self, now: i64, id: Option<i64>) -> Card {
+    Card {
+      id,
+      state: CardState::New,
+      step: None,
+      stability: None,
+      difficulty: None,
+      due: now,
+      last_review: None,
+      scheduled_days: 0,
+      reps: 0,
+      lapses: 0,
+    }
+  }
+
+  /// Review `card` with `rating` (1-4) at `reviewTime`, and return the updated card
+  /// together with a log entry for the review.
+  #[napi]
+  pub fn review(&self, card: Card, rating: u32, review_time: i64) -> Result<ReviewResult> {
+    if !(1..=4).contains(&rating) {
+      return Err(napi_error("rating must be between 1 and 4"));
+    }
+    let memory_state = match (card.state, card.stability, card.difficulty) {
+      (CardState::New, _, _) => None,
+      (_, Some(stability), Some(difficulty)) => Some(fsrs::MemoryState {
+        stability: stability as f32,
+        difficulty: difficulty as f32,
+      }),
+      _ => {
+        return Err(napi_error(
+          "Card stability and difficulty are required once it has been reviewed",
+        ));
+      }
+    };
+    let elapsed_days = card.last_review.map_or(0, |last_review| {
+      (review_time - last_review).div_euclid(MS_PER_DAY).max(0) as u32
+    });
+    let next_states = self
+      .model
+      .next_states(memory_state, self.desired_retention, elapsed_days)
+      .map_err(|e| fsrs_error("nextStates", e))?;
+    let next_state = match rating {
+      1 => next_states.again,
+      2 => next_states.hard,
+      3 => next_states.good,
+      _ => next_states.easy,
+    };
+
+    let mut next = Card {
+      stability: Some(next_state.memory.stability as f64),
+      difficulty: Some(next_state.memory.difficulty as f64),
+      last_review: Some(review_time),
+      reps: card.reps + 1,
+      ..card.clone()
+    };
+    let transition = match card.state {
+      CardState::New | CardState::Learning => {
+        next_step(&self.learning_steps, card.step.unwrap_or(0), rating)
+          .map(|step| (CardState::Learning, step))
+      }
+      CardState::Relearning => next_step(&self.relearning_steps, card.step.unwrap_or(0), rating)
+        .map(|step| (CardState::Relearning, step)),
+      CardState::Review if rating == 1 => {
+        next.lapses += 1;
+        self
+          .relearning_steps
+          .first()
+          .map(|&delay| (CardState::Relearning, (0, delay)))
+      }
+      CardState::Review => None,
+    };
+
+    match transition {
+      Some((state, (step, delay))) => {
+        next.state = state;
+        next.step = Some(step);
+        next.due = review_time + (delay * MS_PER_MINUTE as f64).round() as i64;
+        next.scheduled_days = 0;
+      }
+      None => {
+        let days = if self.enable_fuzz {
+          fuzz::fuzzed_days(
+            next_state.interval as f64,
+            elapsed_days,
+            &FuzzOption {
+              card_id: card.id.unwrap_or_default(),
+              review_count: card.reps,
+              maximum_interval: Some(self.maximum_interval),
+            },
+          )
+        } else {
+          (next_state.interval.round() as u32).clamp(1, self.maximum_interval)
+        };
+        next.state = CardState::Review;
+        next.step = None;
+        next.due = review_time + days as i64 * MS_PER_DAY;
+        next.scheduled_days = days;
+      }
+    }
+
+    Ok(ReviewResult {
+      log: ReviewLog {
+        rating,
+        state: card.state,
+        due: card.due,
+        stability: card.stability,
+        difficulty: card.difficulty,
+        elapsed_days,
+        scheduled_days: next.scheduled_days,
+        review_time,
+      },
+      card: next,
+    })
+  }
+}
+
+/// The next (re)learning step and its delay in minutes, or `None` if the card graduates.
+fn next_step(steps: &[f64], step: u32, rating: u32) -> Option<(u32, f64)> {
+  let step = step as usize;
+  if steps.is_empty() || (step >= steps.len() && rating != 1) {
+    return None;
+  }
+  match rating {
+    1 => Some((0, steps[0])),
+    // Hard repeats the current step, waiting between it and the next one on the first step
+    2 => {
+      let delay = match (step, steps.len()) {
+        (0, 1) => steps[0] * 1.5,
+        (0, _) => (steps[0] + steps[1]) / 2.0,
+        _ => steps[step],
+      };
+      Some((step as u32, delay))
+    }
+    3 if step + 1 < steps.len() => Some((step as u32 + 1, steps[step + 1])),
+    _ => None,
+  }
+}
@@ -0,0 +1,157 @@
+use napi::bindgen_prelude::{Env, JsFunction, Result, ToNapiValue};
+use napi::threadsafe_function::{ErrorStrategy, ThreadsafeFunction, ThreadsafeFunctionCallMode};
+use napi::{JsObject, JsUnknown, Status, ValueType};
+use std::sync::{Arc, Mutex, mpsc};
+
+use crate::{PostSchedulingContext, SimulatorCard, napi_error};
+
+/// The first error a JS hook raised during a simulation. Once set, the remaining hook
+/// calls fall back to the simulator's default behaviour.
+pub(crate) type HookError = Arc<Mutex<Option<napi::Error>>>;
+
+/// Returns the error a hook raised, if any, so the simulation can be rejected with it.
+pub(crate) fn check_hook_error(hook_error: &HookError) -> Result<()> {
+  match hook_error.lock().unwrap().take() {
+    Some(error) => Err(error),
+    None => Ok(()),
+  }
+}
+
+/// Wraps `callback` so it returns `{ value }`, or `{ error }` if it throws. An exception
+/// escaping a threadsafe function call that waits for the return value aborts the process.
+fn guard(env: Env, callback: JsFunction) -> Result<JsFunction> {
+  let guard = env.create_function_from_closure("guard", |ctx| {
+    let callback = ctx.get::<JsFunction>(0)?;
+    let args = ctx.get_all().into_iter().skip(1).collect::<Vec<_>>();
+    let mut result = ctx.env.create_object()?;
+    match callback.call(None, &args) {
+      Ok(value) => result.set_named_property("value", value)?,
+      Err(error) => result.set_named_property("error", ctx.env.create_string(&error.reason)?)?,
+    }
+    Ok(result)
+  })?;
+  let guard = guard.coerce_to_object()?;
+  let bind: JsFunction = guard.get_named_property("bind")?;
+  bind
+    .call(
+      Some(&guard),
+      &[env.get_null()?.into_unknown(), callback.into_unknown()],
+    )?
+    .try_into()
+}
+
+fn hook_result(name: &str, result: JsObject) -> Result<f64> {
+  if result.has_named_property("error")? {
+    let error = result
+      .get_named_property::<JsUnknown>("error")?
+      .coerce_to_string()?
+      .into_utf8()?
+      .into_owned()?;
+    return Err(napi_error(format!("{name} threw: {error}")));
+  }
+  let value = result.get_named_property::<JsUnknown>("value")?;
+  if value.get_type()? != ValueType::Number {
+    return Err(napi_error(format!("{name} must return a number")));
+  }
+  let value = value.coerce_to_number()?.get_double()?;
+  if !value.is_finite() {
+    return Err(napi_error(format!("{name} must return a finite number")));
+  }
+  Ok(value)
+}
+
+/// A JS callback the simulator can call synchronously from its worker threads.
+///
+/// Each call blocks until the JS thread has run the callback, so the simulation
+/// must not run on the JS thread itself.
+struct JsHook<T: 'static> {
+  name: &'static str,
+  function: ThreadsafeFunction<T, ErrorStrategy::Fatal>,
+  error: HookError,
+}
+
+impl<T: ToNapiValue + 'static> JsHook<T> {
+  fn new(env: Env, name: &'static str, callback: JsFunction, error: HookError) -> Result<Self> {
+    let function =
+      guard(env, callback)?.create_threadsafe_function(0, |ctx| Ok(vec![ctx.value]))?;
+    Ok(Self {
+      name,
+      function,
+      error,
+    })
+  }
+
+  /// `None` if this or another hook failed, in which case the caller uses its default.
+  fn call(&self, args: T) -> Option<f64> {
+    if self.error.lock().unwrap().is_some() {
+      return None;
+    }
+    let name = self.name;
+    let (sender, receiver) = mpsc::channel();
+    let status = self.function.call_with_return_value(
+      args,
+      ThreadsafeFunctionCallMode::Blocking,
+      move |result: JsObject| {
+        let _ = sender.send(hook_result(name, result));
+        Ok(())
+      },
+    );
+    let result = if status == Status::Ok {
+      receiver
+        .recv()
+        .unwrap_or_else(|_| Err(napi_error(format!("{name} was not called"))))
+    } else {
+      Err(napi_error(format!(
+        "Failed to call {name}, status = {status:?}"
+      )))
+    };
+    match result {
+      Ok(value) => Some(value),
+      Err(error) => {
+        self.error.lock().unwrap().get_or_insert(error);
+        None
+      }
+    }
+  }
+}
+
+/// The returned interval is clamped between 1 day and `maxInterval`.
+pub(crate) fn post_scheduling_fn(
+  env: Env,
+  callback: JsFunction,
+  error: HookError,
+) -> Result<fsrs::PostSchedulingFn> {
+  let hook = JsHook::new(env, "postSchedulingFn", callback, error)?;
+  Ok(fsrs::PostSchedulingFn::new(move |mut ctx| {
+    let random = ctx.random_f32() as f64;
+    let context = PostSchedulingContext {
+      card: SimulatorCard::from(ctx.card),
+      max_interval: ctx.max_interval as f64,
+      today: ctx.today as u32,
+      due_counts_per_day: ctx
+        .due_counts_per_day
+        .iter()
+        .map(|&count| count as u32)
+        .collect(),
+      random,
+    };
+    hook
+      .call(context)
+      .map_or(ctx.card.interval, |interval| interval as f32)
+      .clamp(1.0, ctx.max_interval)
+  }))
+}
+
+pub(crate) fn review_priority_fn(
+  env: Env,
+  callback: JsFunction,
+  error: HookError,
+) -> Result<fsrs::ReviewPriorityFn> {
+  let hook = JsHook::new(env, "reviewPriorityFn", callback, error)?;
+  let default = fsrs::ReviewPriorityFn::default();
+  Ok(fsrs::ReviewPriorityFn::new(move |card| {
+    hook
+      .call(SimulatorCard::from(card))
+      .map_or_else(|| default(card), |priority| priority as i32)
+  }))
+}
@@ -1,12 +1,129 @@
 use napi::bindgen_prelude::{Env, Error, Result, Status, Task};
 use napi::threadsafe_function::{ErrorStrategy, ThreadsafeFunction, ThreadsafeFunctionCallMode};
-use std::sync::Arc;
+use napi::{JsFunction, JsObject, JsUnknown, Ref};
+use std::sync::{Arc, Mutex};
+
+use crate::simulator_hooks::{HookError, check_hook_error};
 
 #[derive(Debug)]
 pub struct ProgressData {
   pub current: usize,
   pub total: usize,
   pub percent: f64,
+  /// Only set while training
+  pub epoch: Option<EpochProgress>,
+}
+
+/// Where a single training split currently is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EpochProgress {
+  pub split_index: usize,
+  pub epoch: usize,
+  pub epoch_total: usize,
+  pub items_processed: usize,
+  pub items_total: usize,
+}
+
+impl ProgressData {
+  pub(crate) fn new(current: usize, total: usize) -> Self {
+    let percent = if total == 0 {
+      0.0
+    } else {
+      current as f64 / total as f64
+    };
+    Self {
+      current,
+      total,
+      percent,
+      epoch: None,
+    }
+  }
+}
+
+impl From<fsrs::ItemProgress> for ProgressData {
+  fn from(progress: fsrs::ItemProgress) -> Self {
+    Self::new(progress.current, progress.total)
+  }
+}
+
+pub(crate) type ProgressCallback = ThreadsafeFunction<ProgressData, ErrorStrategy::CalleeHandled>;
+
+fn report_progress(progress_callback: &ProgressCallback, progress_data: ProgressData) {
+  let status = progress_callback.call(Ok(progress_data), ThreadsafeFunctionCallMode::NonBlocking);
+
+  if status != napi::Status::Ok {
+    eprintln!("Failed to call JS callback, status = {status:?}");
+  }
+}
+
+pub(crate) type SharedProgressState = Arc<Mutex<fsrs::CombinedProgressState>>;
+
+/// The same error napi rejects with when an `AbortSignal` cancels a queued task.
+fn abort_error() -> Error {
+  Error::new(Status::Cancelled, "AbortError".to_string())
+}
+
+/// The `abort` listener a task added to its `AbortSignal`, removed again once the task
+/// settles so the signal doesn't keep the task's progress state alive.
+pub(crate) struct AbortListener {
+  pub(crate) signal: Ref<()>,
+  pub(crate) listener: Ref<()>,
+}
+
+impl AbortListener {
+  fn remove(mut self, env: Env) -> Result<()> {
+    let signal: JsObject = env.get_reference_value(&self.signal)?;
+    let listener: JsFunction = env.get_reference_value(&self.listener)?;
+    let remove_event_listener: JsFunction = signal.get_named_property("removeEventListener")?;
+    let result = remove_event_listener.call(
+      Some(&signal),
+      &[
+        env.create_string("abort")?.into_unknown(),
+        listener.into_unknown(),
+      ] as &[JsUnknown],
+    );
+    self.signal.unref(env)?;
+    self.listener.unref(env)?;
+    result.map(|_| ())
+  }
+}
+
+fn remove_abort_listener(env: Env, abort_listener: &mut Option<AbortListener>) -> Result<()> {
+  abort_listener
+    .take()
+    .map_or(Ok(()), |abort_listener| abort_listener.remove(env))
+}
+
+fn want_abort(progress_state: &SharedProgressState) -> bool {
+  progress_state.lock().unwrap().want_abort
+}
+
+/// A `ComputeParametersInput` whose items are only copied out of a shared `Dataset` when
+/// it is used, on the task's thread for background tasks.
+#[derive(Default)]
+pub(crate) struct SharedComputeParametersInput {
+  pub(crate) train_set: Arc<Vec<fsrs::FSRSItem>>,
+  /// Everything but `train_set`
+  pub(crate) input: fsrs::ComputeParametersInput,
+}
+
+impl SharedComputeParametersInput {
+  pub(crate) fn into_fsrs(self) -> fsrs::ComputeParametersInput {
+    fsrs::ComputeParametersInput {
+      train_set: Arc::unwrap_or_clone(self.train_set),
+      ..self.input
+    }
+  }
+}
+
+fn fsrs_task_error(action: &str, error: fsrs::FSRSError) -> Error {
+  if error == fsrs::FSRSError::Interrupted {
+    return abort_error();
+  }
+  Error::new(
+    Status::GenericFailure,
+    format!("FSRS {action} failed: {error}"),
+  )
 }
 
 /// A background task that runs `compute_parameters`, sending progress updates via TSFN.
@@ -17,12 +134,14 @@ pub struct ComputeParametersTask {
   pub(crate) training_config: Option<fsrs::TrainingConfig>,
   pub(crate) card_ids: Option<Vec<i64>>,
 
-  pub(crate) train_data: Vec<fsrs::FSRSItem>,
+  pub(crate) train_data: Arc<Vec<fsrs::FSRSItem>>,
   // The threadsafe JS callback for partial updates
-  pub(crate) progress_callback:
-    Option<ThreadsafeFunction<ProgressData, ErrorStrategy::CalleeHandled>>,
+  pub(crate) progress_callback: Option<ProgressCallback>,
 
   pub(crate) progress_timeout: u64,
+  // Shared with the `AbortSignal` listener, which sets `want_abort`
+  pub(crate) progress_state: SharedProgressState,
+  pub(crate) abort_listener: Option<AbortListener>,
 }
 
 impl Task for ComputeParametersTask {
@@ -30,11 +149,14 @@ impl Task for ComputeParametersTask {
   type JsValue = Vec<f64>;
 
   fn compute(&mut self) -> Result<Self::Output> {
-    // 1) Create a shared progress object
-    let progress_state = fsrs::CombinedProgressState::new_shared();
+    // 1) Bail out early if the signal fired before the task was scheduled
+    let progress_state = Arc::clone(&self.progress_state);
+    if want_abort(&progress_state) {
+      return Err(abort_error());
+    }
     let progress_state_for_thread = Arc::clone(&progress_state);
     // Clone what we need for the separate thread
-    let train_data = self.train_data.clone();
+    let train_data = Arc::unwrap_or_clone(std::mem::take(&mut self.train_data));
     let card_ids = self.card_ids.clone();
     let enable_short_term = self.enable_short_term;
     let num_relearning_steps = self.num_relearning_steps;
@@ -51,39 +173,49 @@ impl Task for ComputeParametersTask {
         num_relearning_steps,
         training_config,
       })
-      .map_err(|e| Error::new(Status::GenericFailure, format!("{e:?}")))
+      .map_err(|e| match e {
+        fsrs::FSRSError::Interrupted => abort_error(),
+        e => Error::new(Status::GenericFailure, format!("{e:?}")),
+      })
     });
 
-    // 3) Meanwhile, on *this* thread, poll `progress_state` in a loop
-    //    and call `progress_callback` with updated progress.
+    // 3) Meanwhile, on *this* thread, poll `progress_state` every `progress_timeout` ms
+    //    and call `progress_callback` for each split whose progress changed.
     if let Some(progress_callback) = &self.progress_callback {
+      let mut reported: Vec<EpochProgress> = Vec::new();
       loop {
-        let (current, total, finished) = {
+        let (current, total, finished, splits) = {
           let p = progress_state.lock().unwrap();
-          (p.current(), p.total(), p.finished())
+          let splits = p
+            .splits
+            .iter()
+            .enumerate()
+            .map(|(split_index, split)| EpochProgress {
+              split_index,
+              epoch: split.epoch,
+              epoch_total: split.epoch_total,
+              items_processed: split.items_processed,
+              items_total: split.items_total,
+            })
+            .collect::<Vec<_>>();
+          (p.current(), p.total(), p.finished(), splits)
         };
 
-        let percent = if total == 0 {
-          0.0
-        } else {
-          current as f64 / total as f64
-        };
-
-        // Call JS callback if you want once per second or whenever progress changes
-        let status = progress_callback.call(
-          Ok(ProgressData {
-            current,
-            total,
-            percent,
-          }),
-          ThreadsafeFunctionCallMode::NonBlocking,
-        );
-
-        if status != napi::Status::Ok {
-          eprintln!("Failed to call JS callback, status = {status:?}");
+        for split in &splits {
+          if reported.get(split.split_index) != Some(split) {
+            report_progress(
+              progress_callback,
+              ProgressData {
+                epoch: Some(*split),
+                ..ProgressData::new(current, total)
+              },
+            );
+          }
         }
+        reported = splits;
 
-        if finished || percent >= 100.0 {
+        // `finished` is not set if the compute thread panicked
+        if finished || handle.is_finished() {
           break;
         }
 
@@ -107,4 +239,181 @@ impl Task for ComputeParametersTask {
   fn resolve(&mut self, _env: Env, output: Self::Output) -> Result<Self::JsValue> {
     Ok(output.iter().map(|&x| x as f64).collect())
   }
+
+  fn finally(&mut self, env: Env) -> Result<()> {
+    remove_abort_listener(env, &mut self.abort_listener)
+  }
+}
+
+/// A background task that runs `FSRS::evaluate`, reporting progress per chunk of items.
+pub struct EvaluateTask {
+  pub(crate) model: Arc<fsrs::FSRS>,
+  pub(crate) train_data: Arc<Vec<fsrs::FSRSItem>>,
+  pub(crate) card_ids: Option<Vec<i64>>,
+  pub(crate) progress_callback: Option<ProgressCallback>,
+}
+
+impl Task for EvaluateTask {
+  type Output = fsrs::ModelEvaluation;
+  type JsValue = crate::ModelEvaluation;
+
+  fn compute(&mut self) -> Result<Self::Output> {
+    let train_data = Arc::unwrap_or_clone(std::mem::take(&mut self.train_data));
+    let progress_callback = self.progress_callback.as_ref();
+    crate::evaluate_model(&self.model, train_data, self.card_ids.take(), |progress| {
+      if let Some(progress_callback) = progress_callback {
+        report_progress(progress_callback, progress.into());
+      }
+      true
+    })
+    .map_err(|e| fsrs_task_error("evaluate", e))
+  }
+
+  fn resolve(&mut self, _env: Env, output: Self::Output) -> Result<Self::JsValue> {
+    Ok(output.into())
+  }
+}
+
+/// A background task that runs `benchmark`.
+///
+/// `fsrs::benchmark` does not report progress, and panics instead of returning an error,
+/// so the panic is caught and turned into a rejection.
+pub struct BenchmarkTask {
+  pub(crate) input: SharedComputeParametersInput,
+}
+
+impl Task for BenchmarkTask {
+  type Output = Vec<f32>;
+  type JsValue = Vec<f64>;
+
+  fn compute(&mut self) -> Result<Self::Output> {
+    let input = std::mem::take(&mut self.input).into_fsrs();
+    std::panic::catch_unwind(move || fsrs::benchmark(input)).map_err(|_| {
+      Error::new(
+        Status::GenericFailure,
+        "Panic occurred in benchmark".to_string(),
+      )
+    })
+  }
+
+  fn resolve(&mut self, _env: Env, output: Self::Output) -> Result<Self::JsValue> {
+    Ok(output.iter().map(|&x| x as f64).collect())
+  }
+}
+
+/// A background task that runs `evaluate_with_time_series_splits`, reporting progress per split.
+pub struct EvaluateWithTimeSeriesSplitsTask {
+  pub(crate) input: SharedComputeParametersInput,
+  pub(crate) progress_callback: Option<ProgressCallback>,
+  pub(crate) progress_state: SharedProgressState,
+  pub(crate) abort_listener: Option<AbortListener>,
+}
+
+impl Task for EvaluateWithTimeSeriesSplitsTask {
+  type Output = fsrs::ModelEvaluation;
+  type JsValue = crate::ModelEvaluation;
+
+  fn compute(&mut self) -> Result<Self::Output> {
+    if want_abort(&self.progress_state) {
+      return Err(abort_error());
+    }
+    let input = std::mem::take(&mut self.input).into_fsrs();
+    let progress_callback = self.progress_callback.as_ref();
+    let progress_state = &self.progress_state;
+    fsrs::evaluate_with_time_series_splits(input, |progress| {
+      if let Some(progress_callback) = progress_callback {
+        report_progress(progress_callback, progress.into());
+      }
+      !want_abort(progress_state)
+    })
+    .map_err(|e| fsrs_task_error("evaluateWithTimeSeriesSplits", e))
+  }
+
+  fn resolve(&mut self, _env: Env, output: Self::Output) -> Result<Self::JsValue> {
+    Ok(output.into())
+  }
+
+  fn finally(&mut self, env: Env) -> Result<()> {
+    remove_abort_listener(env, &mut self.abort_listener)
+  }
+}
+
+/// A background task that searches for the optimal desired retention, reporting progress
+/// before each simulated retention.
+pub struct OptimalRetentionTask {
+  pub(crate) config: fsrs::SimulatorConfig,
+  pub(crate) parameters: Vec<f32>,
+  pub(crate) hook_error: HookError,
+  pub(crate) progress_callback: Option<ProgressCallback>,
+  pub(crate) progress_state: SharedProgressState,
+  pub(crate) abort_listener: Option<AbortListener>,
+}
+
+impl Task for OptimalRetentionTask {
+  type Output = (f32, Vec<(f32, f32)>);
+  type JsValue = crate::OptimalRetention;
+
+  fn compute(&mut self) -> Result<Self::Output> {
+    let progress_callback = self.progress_callback.as_ref();
+    let progress_state = &self.progress_state;
+    let hook_error = &self.hook_error;
+    let result = crate::optimal_retention::cost_curve(&self.config, &self.parameters, |current| {
+      if let Some(progress_callback) = progress_callback {
+        // The number of retentions the search evaluates is not known in advance.
+        report_progress(progress_callback, ProgressData::new(current, 0));
+      }
+      !want_abort(progress_state) && hook_error.lock().unwrap().is_none()
+    });
+    check_hook_error(hook_error)?;
+    result.map_err(|e| fsrs_task_error("computeOptimalRetention", e))
+  }
+
+  fn resolve(&mut self, _env: Env, output: Self::Output) -> Result<Self::JsValue> {
+    let (desired_retention, cost_curve) = output;
+    Ok(crate::OptimalRetention {
+      desired_retention: desired_retention as f64,
+      cost_curve: cost_curve
+        .into_iter()
+        .map(|(desired_retention, cost)| crate::RetentionCost {
+          desired_retention: desired_retention as f64,
+          cost: cost as f64,
+        })
+        .collect(),
+    })
+  }
+
+  fn finally(&mut self, env: Env) -> Result<()> {
+    remove_abort_listener(env, &mut self.abort_listener)
+  }
+}
+
+/// A background task that runs `simulate`, so the simulator can wait on JS hooks.
+pub struct SimulateTask {
+  pub(crate) config: fsrs::SimulatorConfig,
+  pub(crate) parameters: Vec<f32>,
+  pub(crate) desired_retention: f32,
+  pub(crate) seed: Option<u64>,
+  pub(crate) existing_cards: Option<Vec<fsrs::Card>>,
+  pub(crate) hook_error: HookError,
+}
+
+impl Task for SimulateTask {
+  type Output = fsrs::SimulationResult;
+  type JsValue = crate::SimulationResult;
+
+  fn compute(&mut self) -> Result<Self::Output> {
+    let result = fsrs::simulate(
+      &self.config,
+      &self.parameters,
+      self.desired_retention,
+      self.seed,
+      self.existing_cards.take(),
+    );
+    check_hook_error(&self.hook_error)?;
+    result.map_err(|e| fsrs_task_error("simulate", e))
+  }
+
+  fn resolve(&mut self, _env: Env, output: Self::Output) -> Result<Self::JsValue> {
+    Ok(output.into())
+  }
 }